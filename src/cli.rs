@@ -1,4 +1,4 @@
-use clap::{Parser, Subcommand, arg};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 
 #[derive(Subcommand, Debug)]
 pub(crate) enum Commands {
@@ -12,6 +12,132 @@ pub(crate) enum Commands {
         name: String,
         #[arg(help = "keywords to be associated with a note")]
         tags: Option<Vec<String>>,
+        #[arg(
+            short,
+            long,
+            help = "populate the body of the new note from a template"
+        )]
+        template: Option<String>,
+        #[arg(
+            long,
+            conflicts_with = "template",
+            help = "create the note without the configured default template"
+        )]
+        no_template: bool,
+    },
+
+    #[command(about = "Open today's journal note, creating it if it does not exist.")]
+    Today {
+        #[arg(long, help = "open yesterday's journal note instead")]
+        yesterday: bool,
+        #[arg(long, help = "create the journal note without opening for editing")]
+        quiet: bool,
+    },
+
+    #[command(about = "Open the journal note of a day, creating it if it does not exist.")]
+    Day {
+        #[arg(help = "the day of the journal note as YYYY-MM-DD")]
+        date: String,
+        #[arg(long, help = "create the journal note without opening for editing")]
+        quiet: bool,
+    },
+
+    #[command(alias = "e", about = "Open an existing note for editing.")]
+    Edit {
+        #[arg(help = "path, filename or title of the note to be edited")]
+        note: String,
+    },
+
+    #[command(alias = "rm", about = "Move notes into the trash of the note stack.")]
+    Remove {
+        #[arg(
+            required = true,
+            help = "path, filename or title of the notes to be removed"
+        )]
+        notes: Vec<String>,
+    },
+
+    #[command(
+        alias = "mv",
+        about = "Move a note to a different category, name or note stack."
+    )]
+    Move {
+        #[arg(help = "path, filename or title of the note to be moved")]
+        note: String,
+        #[arg(
+            required_unless_present = "to_stack",
+            help = "new category and name of the note, e.g. category/subcategory/name"
+        )]
+        destination: Option<String>,
+        #[arg(
+            long,
+            help = "note stack to move the note into, at the same category path unless a destination is given"
+        )]
+        to_stack: Option<String>,
+    },
+
+    #[command(
+        alias = "cp",
+        about = "Copy a note to a different category, name or note stack."
+    )]
+    Copy {
+        #[arg(help = "path, filename or title of the note to be copied")]
+        note: String,
+        #[arg(
+            required_unless_present = "to_stack",
+            help = "category and name of the copy, e.g. category/subcategory/name"
+        )]
+        destination: Option<String>,
+        #[arg(
+            long,
+            help = "note stack to copy the note into, at the same category path unless a destination is given"
+        )]
+        to_stack: Option<String>,
+    },
+
+    #[command(about = "List the [[links]] from a note to other notes.")]
+    Links {
+        #[arg(help = "path, filename or title of the note")]
+        note: String,
+    },
+
+    #[command(about = "List the notes that [[link]] to a note.")]
+    Backlinks {
+        #[arg(help = "path, filename or title of the note")]
+        note: String,
+    },
+
+    #[command(about = "Check notes for malformed front matter and broken links.")]
+    Check,
+
+    #[command(about = "Add, remove or set the tags of existing notes.")]
+    Tag {
+        #[command(subcommand)]
+        tag: Tag,
+    },
+
+    #[command(about = "Hide notes from being listed.")]
+    Hide {
+        #[arg(
+            required = true,
+            help = "path, filename or title of the notes to be hidden"
+        )]
+        notes: Vec<String>,
+    },
+
+    #[command(about = "Show previously hidden notes when listing.")]
+    Unhide {
+        #[arg(
+            required = true,
+            help = "path, filename or title of the notes to be shown"
+        )]
+        notes: Vec<String>,
+    },
+
+    #[command(about = "List, restore or empty removed notes.")]
+    Trash {
+        #[command(subcommand)]
+        trash: Option<Trash>,
     },
 
     #[command(alias = "ls", about = "List available notes in note stack.")]
@@ -41,10 +167,83 @@ pub(crate) enum Commands {
         )]
         short: bool,
 
+        #[arg(
+            short,
+            long = "tag",
+            help = "only list notes with the given tag, may be repeated"
+        )]
+        tags: Vec<String>,
+
+        #[arg(
+            long,
+            value_enum,
+            default_value_t = TagMatch::Any,
+            requires = "tags",
+            help = "whether notes must have any or all of the given tags"
+        )]
+        tag_match: TagMatch,
+
+        #[arg(
+            long,
+            value_enum,
+            conflicts_with_all = &["full", "short", "root"],
+            help = "print each entry as a machine-readable record"
+        )]
+        format: Option<Format>,
+
+        #[arg(long, conflicts_with = "all", help = "only list hidden notes")]
+        hidden: bool,
+
+        #[arg(short, long, help = "list hidden notes alongside visible notes")]
+        all: bool,
+
         #[arg(help = "match string to filter by")]
         category: Option<String>,
     },
 
+    #[command(about = "List every tag and the number of notes using it.")]
+    Tags {
+        #[arg(help = "match string to filter by")]
+        category: Option<String>,
+    },
+
+    #[command(alias = "grep", about = "Search the contents of notes.")]
+    Search {
+        #[arg(
+            short = 'e',
+            long,
+            help = "interpret the pattern as a regular expression"
+        )]
+        regex: bool,
+
+        #[arg(short, long, help = "ignore case distinctions when matching")]
+        ignore_case: bool,
+
+        #[arg(
+            short = 'C',
+            long,
+            default_value_t = 0,
+            help = "number of lines of context to print around each match"
+        )]
+        context: usize,
+
+        #[arg(short, long, help = "match string to filter notes by")]
+        category: Option<String>,
+
+        #[arg(help = "the text to search for")]
+        pattern: String,
+    },
+
+    #[command(about = "Run a background daemon that indexes and caches note stacks.")]
+    Daemon {
+        #[arg(
+            long,
+            default_value_t = 2,
+            help = "seconds between checks of the note stacks for changes"
+        )]
+        interval: u64,
+    },
+
     #[command(about = "Notes configuration.")]
     Config {
         #[arg(long, help = "prints the notes configuration directory")]
@@ -63,6 +262,91 @@ pub(crate) enum Commands {
     Save {
         #[arg(long, help = "Store commits and push to remote repository")]
         remote: bool,
+
+        #[arg(
+            short,
+            long,
+            conflicts_with = "each",
+            help = "Commit message, summarised from the changed notes when omitted"
+        )]
+        message: Option<String>,
+
+        #[arg(long, help = "Commit each changed note separately")]
+        each: bool,
+    },
+
+    #[command(about = "Save notes and exchange them with the remote repository.")]
+    Sync {
+        #[arg(long, help = "Merge remote changes rather than rebasing onto them")]
+        merge: bool,
+
+        #[arg(
+            long,
+            conflicts_with = "merge",
+            help = "Abandon a merge stopped on conflicts"
+        )]
+        abort: bool,
+    },
+
+    #[command(
+        name = "merge-driver",
+        hide = true,
+        about = "Git merge driver for notes, merging front matter field by field."
+    )]
+    MergeDriver {
+        #[arg(help = "common ancestor version of the note (%O)")]
+        ancestor: std::path::PathBuf,
+
+        #[arg(help = "current version of the note, overwritten with the result (%A)")]
+        current: std::path::PathBuf,
+
+        #[arg(help = "other version of the note (%B)")]
+        other: std::path::PathBuf,
+    },
+
+    #[command(about = "List the saved versions of a note.")]
+    History {
+        #[arg(help = "path, filename or title of the note")]
+        note: String,
+    },
+
+    #[command(about = "Print a saved version of a note.")]
+    Show {
+        #[arg(help = "note and revision as <note>@<rev>, the last saved version when omitted")]
+        note: String,
+    },
+
+    #[command(about = "Show changes to a note since a saved version.")]
+    Diff {
+        #[arg(help = "path, filename or title of the note")]
+        note: String,
+
+        #[arg(help = "revision to compare with, the last saved version when omitted")]
+        rev: Option<String>,
+    },
+
+    #[command(about = "Restore a deleted or earlier version of a note.")]
+    Restore {
+        #[arg(help = "path, filename or title of the note")]
+        note: String,
+
+        #[arg(help = "revision to restore, the last saved version when omitted")]
+        rev: Option<String>,
+
+        #[arg(long, help = "overwrite changes to the note that have not been saved")]
+        force: bool,
+    },
+
+    #[command(about = "Import a folder of markdown notes into the note stack.")]
+    Import {
+        #[arg(help = "directory of markdown notes, such as an obsidian vault")]
+        dir: std::path::PathBuf,
+    },
+
+    #[command(about = "Export the note stack to other formats.")]
+    Export {
+        #[command(subcommand)]
+        export: Export,
     },
 
     #[command(about = "Note stack operations and subcommands.")]
@@ -72,6 +356,68 @@ pub(crate) enum Commands {
     },
 }
 
+#[derive(Subcommand, Debug)]
+pub enum Tag {
+    #[command(about = "Add tags to a note")]
+    Add(TagEdit),
+
+    #[command(aliases=&["rm"], about = "Remove tags from a note")]
+    Remove(TagEdit),
+
+    #[command(about = "Replace the tags of a note")]
+    Set(TagEdit),
+}
+
+#[derive(Args, Debug)]
+pub struct TagEdit {
+    #[arg(
+        long,
+        help = "apply to every note matched by the filters rather than a single note"
+    )]
+    pub(crate) batch: bool,
+
+    #[arg(
+        short,
+        long,
+        requires = "batch",
+        help = "match string to filter notes by in batch mode"
+    )]
+    pub(crate) category: Option<String>,
+
+    #[arg(
+        long = "with-tag",
+        requires = "batch",
+        help = "only apply to notes with the given tag in batch mode, may be repeated"
+    )]
+    pub(crate) with_tags: Vec<String>,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = TagMatch::Any,
+        requires = "with_tags",
+        help = "whether notes must have any or all of the filtered tags"
+    )]
+    pub(crate) tag_match: TagMatch,
+
+    #[arg(help = "the note followed by the tags, or only the tags in batch mode")]
+    pub(crate) args: Vec<String>,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum TagMatch {
+    Any,
+    All,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum Format {
+    Json,
+    Ndjson,
+    Csv,
+    Tsv,
+}
+
 #[derive(Subcommand, Debug)]
 pub enum Stack {
     #[command(about = "Create a notes stack")]
@@ -79,6 +425,57 @@ pub enum Stack {
 
     #[command(aliases=&["rm"], about = "Remove a notes stack")]
     Remove { stack: String },
+
+    #[command(aliases=&["mv"], about = "Rename a notes stack")]
+    Rename {
+        #[arg(help = "stack to rename")]
+        old: String,
+
+        #[arg(help = "new name of the stack")]
+        new: String,
+    },
+
+    #[command(aliases=&["cp"], about = "Copy the notes of a stack into a new stack")]
+    Copy {
+        #[arg(help = "stack to copy")]
+        src: String,
+
+        #[arg(help = "name of the new stack")]
+        dst: String,
+    },
+
+    #[command(about = "Move every note of a stack into another stack")]
+    Merge {
+        #[arg(help = "stack the notes are moved out of")]
+        src: String,
+
+        #[arg(help = "stack the notes are moved into")]
+        dst: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Export {
+    #[command(about = "Export visible notes as a static html site")]
+    Html {
+        #[arg(help = "directory the site is written to")]
+        outdir: std::path::PathBuf,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Trash {
+    #[command(aliases=&["ls"], about = "List notes in the trash")]
+    List,
+
+    #[command(about = "Restore a note from the trash to where it was removed from")]
+    Restore {
+        #[arg(help = "trash id, original path or filename of the note")]
+        note: String,
+    },
+
+    #[command(about = "Permanently delete every note in the trash")]
+    Empty,
 }
 
 #[derive(Parser, Debug)]
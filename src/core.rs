@@ -1,4 +1,11 @@
 pub mod actions;
+mod editor;
+mod frontmatter;
+mod git;
+mod index;
 pub mod io;
+mod links;
 mod markdown;
-mod frontmatter;
+mod merge;
+mod resolve;
+mod template;
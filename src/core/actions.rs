@@ -1,12 +1,31 @@
+mod check;
 mod config;
 mod create;
+mod daemon;
+mod edit;
+mod export;
+mod hide;
+mod history;
+mod import;
+mod journal;
+mod links;
 mod list;
+mod merge_driver;
+mod mv;
+mod remove;
 mod save;
+mod search;
 mod stack;
 mod switch;
+mod sync;
+mod tag;
+mod tags;
+mod trash;
 
 use std::error::Error;
 
+pub(crate) use trash::TRASH_DIR;
+
 use crate::{cli::Commands, system::Configuration};
 
 pub trait Command<'a> {
@@ -21,10 +40,40 @@ pub trait Command<'a> {
 pub fn new(conf: &Configuration, args: Commands) -> Result<(), Box<dyn Error>> {
     match args {
         Commands::Create { .. } => create::CreateCommand::new(args, conf)?.execute(),
+        Commands::Today { .. } | Commands::Day { .. } => {
+            journal::JournalCommand::new(args, conf)?.execute()
+        }
+        Commands::Edit { .. } => edit::EditCommand::new(args, conf)?.execute(),
+        Commands::Remove { .. } => remove::RemoveCommand::new(args, conf)?.execute(),
+        Commands::Move { .. } | Commands::Copy { .. } => {
+            mv::MoveCommand::new(args, conf)?.execute()
+        }
+        Commands::Links { .. } | Commands::Backlinks { .. } => {
+            links::LinksCommand::new(args, conf)?.execute()
+        }
+        Commands::Check => check::CheckCommand::new(args, conf)?.execute(),
+        Commands::Tag { .. } => tag::TagCommand::new(args, conf)?.execute(),
+        Commands::Hide { .. } | Commands::Unhide { .. } => {
+            hide::HideCommand::new(args, conf)?.execute()
+        }
+        Commands::Trash { .. } => trash::TrashCommand::new(args, conf)?.execute(),
         Commands::List { .. } => list::ListCommand::new(args, conf)?.execute(),
+        Commands::Search { .. } => search::SearchCommand::new(args, conf)?.execute(),
+        Commands::Tags { .. } => tags::TagsCommand::new(args, conf)?.execute(),
+        Commands::Daemon { .. } => daemon::DaemonCommand::new(args, conf)?.execute(),
         Commands::Config { .. } => config::ConfigurationCommand::new(args, conf)?.execute(),
         Commands::Switch { .. } => switch::SwitchCommand::new(args, conf)?.execute(),
         Commands::Save { .. } => save::SaveCommand::new(args, conf)?.execute(),
+        Commands::Sync { .. } => sync::SyncCommand::new(args, conf)?.execute(),
+        Commands::MergeDriver { .. } => {
+            merge_driver::MergeDriverCommand::new(args, conf)?.execute()
+        }
+        Commands::History { .. }
+        | Commands::Show { .. }
+        | Commands::Diff { .. }
+        | Commands::Restore { .. } => history::HistoryCommand::new(args, conf)?.execute(),
+        Commands::Import { .. } => import::ImportCommand::new(args, conf)?.execute(),
+        Commands::Export { .. } => export::ExportCommand::new(args, conf)?.execute(),
         Commands::Stack { ref stack } => {
             if let Some(stack) = stack {
                 match stack {
@@ -34,6 +83,15 @@ pub fn new(conf: &Configuration, args: Commands) -> Result<(), Box<dyn Error>> {
                     crate::cli::Stack::Remove { .. } => {
                         stack::RemoveCommand::new(args, conf)?.execute()
                     }
+                    crate::cli::Stack::Rename { .. } => {
+                        stack::RenameCommand::new(args, conf)?.execute()
+                    }
+                    crate::cli::Stack::Copy { .. } => {
+                        stack::CopyCommand::new(args, conf)?.execute()
+                    }
+                    crate::cli::Stack::Merge { .. } => {
+                        stack::MergeCommand::new(args, conf)?.execute()
+                    }
                 }
             } else {
                 stack::default(conf)
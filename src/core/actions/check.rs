@@ -0,0 +1,180 @@
+use std::{
+    collections::HashMap,
+    error::Error,
+    io::{IsTerminal, Write},
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Local};
+use termcolor::{Color, StandardStream, WriteColor};
+
+use crate::{
+    core::{
+        frontmatter, links,
+        markdown::NotesFrontMatter,
+        resolve::{self, Catalog},
+    },
+    system::Configuration,
+    write_coloured,
+};
+
+use super::{Command, Commands};
+
+/// A problem found within a note
+struct Problem {
+    path: PathBuf,
+    line: Option<usize>,
+    message: String,
+}
+
+/// Representation of a command that validates every note within a note stack
+pub struct CheckCommand {
+    root: PathBuf,
+    problems: Vec<Problem>,
+}
+
+impl Command<'_> for CheckCommand {
+    fn new(args: Commands, conf: &Configuration) -> Result<Self, Box<dyn Error>> {
+        let Commands::Check = args else {
+            unreachable!("Non-check command passed to check handler.");
+        };
+
+        Ok(Self {
+            root: PathBuf::from(&conf.settings.path),
+            problems: Vec::new(),
+        })
+    }
+
+    fn execute(mut self) -> Result<(), Box<dyn Error>> {
+        let catalog = Catalog::new(&self.root)?;
+        let mut titles = HashMap::<String, Vec<&Path>>::new();
+        let mut count = 0;
+
+        for note in catalog.notes() {
+            count += 1;
+            let contents = match std::fs::read_to_string(note) {
+                Ok(contents) => contents,
+                Err(err) => {
+                    self.report(note, None, format!("unreadable note: {err}"));
+                    continue;
+                }
+            };
+
+            let (body, offset) = match frontmatter::split(&contents) {
+                Some((yaml, body)) => {
+                    match frontmatter::generate(yaml) {
+                        Ok(fm) => {
+                            self.check_frontmatter(note, &fm);
+                            titles
+                                .entry(fm.title.to_lowercase())
+                                .or_default()
+                                .push(note);
+                        }
+                        Err(err) => self.report(note, None, format!("invalid front matter: {err}")),
+                    }
+                    (body, yaml.lines().count() + 2)
+                }
+                None => {
+                    self.report(note, None, "missing front matter".into());
+                    (contents.as_str(), 0)
+                }
+            };
+
+            for link in links::wiki(body, offset) {
+                if catalog.link(note, &link.target).is_none() {
+                    self.report(
+                        note,
+                        Some(link.line),
+                        format!("broken link [[{}]]", link.target),
+                    );
+                }
+            }
+            for link in links::markdown(body, offset) {
+                let target = note
+                    .parent()
+                    .expect("notes are within a note stack")
+                    .join(&link.target);
+                if !target.try_exists()? {
+                    self.report(
+                        note,
+                        Some(link.line),
+                        format!("broken link ({})", link.target),
+                    );
+                }
+            }
+        }
+
+        for notes in titles.values().filter(|notes| notes.len() > 1) {
+            for note in notes {
+                let others = notes
+                    .iter()
+                    .filter(|other| other != &note)
+                    .map(|other| resolve::relative(&self.root, other))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                self.report(note, None, format!("duplicate title also used by {others}"));
+            }
+        }
+
+        if self.problems.is_empty() {
+            println!("checked {count} note(s), no problems found");
+            return Ok(());
+        }
+
+        self.problems
+            .sort_by(|a, b| a.path.cmp(&b.path).then(a.line.cmp(&b.line)));
+        let mut out = StandardStream::stdout(termcolor::ColorChoice::Always);
+        for problem in &self.problems {
+            write_coloured!(out, colour = Color::Green, "{}", problem.path.display());
+            if let Some(line) = problem.line {
+                write!(out, ":")?;
+                write_coloured!(out, colour = Color::Yellow, "{line}");
+            }
+            writeln!(out, ": {}", problem.message)?;
+        }
+        Err(format!(
+            "found {} problem(s) in {count} note(s)",
+            self.problems.len()
+        )
+        .into())
+    }
+}
+
+impl CheckCommand {
+    fn report(&mut self, path: &Path, line: Option<usize>, message: String) {
+        self.problems.push(Problem {
+            path: path.to_path_buf(),
+            line,
+            message,
+        });
+    }
+
+    /// Validates the date and that the category matches the directory of the note
+    fn check_frontmatter(&mut self, path: &Path, fm: &NotesFrontMatter) {
+        if let Err(err) = fm.date.parse::<DateTime<Local>>() {
+            self.report(path, None, format!("unparseable date '{}': {err}", fm.date));
+        }
+
+        let directories = Path::new(&resolve::relative(&self.root, path))
+            .parent()
+            .map(|p| {
+                p.iter()
+                    .map(|c| c.to_string_lossy().to_string())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        let mut categories = Vec::from_iter(fm.notes_metadata.category.clone());
+        categories.extend(fm.notes_metadata.subcategories.clone().unwrap_or_default());
+        if directories != categories {
+            self.report(
+                path,
+                None,
+                format!(
+                    "category '{}' does not match directory '{}'",
+                    categories.join("/"),
+                    directories.join("/")
+                ),
+            );
+        }
+    }
+}
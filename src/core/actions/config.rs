@@ -1,8 +1,14 @@
-use std::io::{Write, stdout};
+use std::{
+    io::{Write, stdout},
+    path::Path,
+};
 
-use crate::system::{CONFIG_FILE, Configuration};
+use crate::{
+    core::editor,
+    system::{CONFIG_FILE, Configuration, Options, STACK_CONFIG_FILE},
+};
 
-use super::{Command, Commands};
+use super::{Command, Commands, journal::DEFAULT_JOURNAL_CATEGORY};
 
 enum ConfigOption {
     Print(bool),
@@ -34,17 +40,45 @@ impl<'a> Command<'a> for ConfigurationCommand<'a> {
         if b {
             writeln!(stdout(), "{}", &*CONFIG_FILE)?;
         } else {
-            println!(
-                r#"notes.toml
-    path={}
-    editor={}"#,
-                self.configuration.settings.path,
-                self.configuration
-                    .settings
-                    .editor
-                    .as_ref()
-                    .map_or("none", |s| s.as_ref())
-            )
+            let conf = self.configuration;
+            let root = Path::new(&conf.settings.path);
+            let stack_file = root.join(STACK_CONFIG_FILE).to_string_lossy().into_owned();
+            // a value that is set came from the stack when the stack overrides it
+            let configured = |key: &str, value: &str| {
+                let origin = if conf.overrides.contains(&key) {
+                    stack_file.as_str()
+                } else {
+                    CONFIG_FILE.as_str()
+                };
+                (value.to_owned(), origin.to_owned())
+            };
+            let default = |value: &str| (value.to_owned(), "default".to_owned());
+
+            let mut out = stdout();
+            writeln!(
+                out,
+                "path={} (active stack '{}')",
+                conf.settings.path,
+                root.file_name().unwrap_or_default().to_string_lossy()
+            )?;
+            let editor = match (conf.settings.editor.as_deref(), editor::environment()) {
+                (Some(editor), _) => configured("editor", editor),
+                (None, Some(editor)) => (editor.to_owned(), "environment".to_owned()),
+                (None, None) => default(editor::DEFAULT_EDITOR),
+            };
+            writeln!(out, "editor={} ({})", editor.0, editor.1)?;
+
+            let unset = Options::default();
+            let options = conf.options.as_ref().unwrap_or(&unset);
+            for (key, value) in options.fields() {
+                let (value, origin) = match (key, value) {
+                    (key, Some(value)) => configured(key, value),
+                    ("hide_root", None) => default("false"),
+                    ("journal_category", None) => default(DEFAULT_JOURNAL_CATEGORY),
+                    (_, None) => default("none"),
+                };
+                writeln!(out, "{key}={value} ({origin})")?;
+            }
         };
         Ok(())
     }
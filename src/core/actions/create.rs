@@ -2,13 +2,14 @@ use std::{
     error::Error,
     ffi::OsStr,
     ops::ControlFlow,
-    path::{Path, PathBuf},
+    path::{Component, Path, PathBuf},
 };
 
-use crate::core::markdown;
+use chrono::Local;
+
+use crate::core::{editor, markdown, template};
 
 use super::{Command, Commands, Configuration};
-const DEFAULT_EDITOR: &str = "vim";
 
 /// Representation of an edit command and the context
 /// needed for an edit command
@@ -17,6 +18,7 @@ pub struct CreateCommand<'a> {
     path: PathBuf,
     category: Option<String>,
     tags: Option<Vec<String>>,
+    body: Option<String>,
     editor: Option<&'a str>,
 }
 
@@ -28,11 +30,18 @@ impl<'a> Command<'a> for CreateCommand<'a> {
             category,
             name,
             tags,
+            template,
+            no_template,
         } = args
         else {
             unreachable!("Non-create command provided to create handler.")
         };
 
+        let category = category.or_else(|| {
+            conf.options
+                .as_ref()
+                .and_then(|o| o.default_category.clone())
+        });
         let mut path = if let Some(category) = &category {
             PathBuf::from(format!(
                 "{}/{}",
@@ -45,31 +54,37 @@ impl<'a> Command<'a> for CreateCommand<'a> {
 
         path.push(format!("{name}.md"));
 
+        let template = if no_template {
+            None
+        } else {
+            template.or_else(|| {
+                conf.options
+                    .as_ref()
+                    .and_then(|o| o.default_template.clone())
+            })
+        };
+        let body = if let Some(template) = template {
+            let root = Path::new(&conf.settings.path);
+            let tags = tags.as_ref().map(|t| t.join(", ")).unwrap_or_default();
+            Some(template::render(
+                &template::load(root, &template)?,
+                &[
+                    ("title", &markdown::title(&name)),
+                    ("date", &Local::now().format("%Y-%m-%d").to_string()),
+                    ("category", category.as_deref().unwrap_or_default()),
+                    ("tags", &tags),
+                    (
+                        "stack",
+                        &root.file_name().unwrap_or_default().to_string_lossy(),
+                    ),
+                ],
+            ))
+        } else {
+            None
+        };
+
         let editor = if !quiet {
-            Some(
-                conf.settings
-                    .editor
-                    .as_deref()
-                    .or_else(|| {
-                        let mut vars = std::env::vars();
-                        let allowed_editors: fn((String, String)) -> Option<&'a str> =
-                            |(_, v)| match v.as_str() {
-                                "nvim" => Some("nvim"),
-                                "glow" => Some("glow"),
-                                _ => None,
-                            };
-                        vars.find(|(key, _)| key == "NOTES_EDITOR")
-                            .and_then(allowed_editors)
-                            .and_then(|s| {
-                                if s == "glow" && !vars.any(|(k, _)| k == "EDITOR") {
-                                    None
-                                } else {
-                                    Some(s)
-                                }
-                            })
-                    })
-                    .unwrap_or(DEFAULT_EDITOR),
-            )
+            Some(editor::resolve(conf))
         } else {
             None
         };
@@ -79,6 +94,7 @@ impl<'a> Command<'a> for CreateCommand<'a> {
             path,
             category,
             tags,
+            body,
             editor,
         })
     }
@@ -102,15 +118,12 @@ impl<'a> Command<'a> for CreateCommand<'a> {
                 .ok_or("a invalid path provided at creation")?,
             self.category.as_deref(),
             self.tags,
+            self.body.as_deref(),
         )
         .write()?;
 
         if let Some(editor) = self.editor {
-            let mut command = std::process::Command::new(editor);
-            if editor == "glow" {
-                command.arg(r#"--tui"#);
-            }
-            command.arg(self.path.as_os_str()).status()?;
+            editor::open(editor, &self.path)?;
         }
 
         Ok(())
@@ -119,7 +132,7 @@ impl<'a> Command<'a> for CreateCommand<'a> {
 
 /// Validates that a filename is represented in the format
 /// <name>+([-]<name>+)?* of only ascii chars
-fn validate_name(name: String) -> Result<String, Box<dyn Error>> {
+pub(super) fn validate_name(name: String) -> Result<String, Box<dyn Error>> {
     if let Some(p) = name.find(|c: char| !(c.is_ascii() && c < 128 as char || c == '-')) {
         let res = name.chars().try_fold((0usize, 0), |(idx, b), elem| {
             if b >= p {
@@ -147,8 +160,15 @@ fn validate_name(name: String) -> Result<String, Box<dyn Error>> {
 // FIXME: Review. May not provided sufficient guarantees.
 // Portable characters can be found in definitions within
 // https://pubs.opengroup.org/onlinepubs/9799919799/
-fn validate_path(path: &str) -> Result<&str, Box<dyn Error>> {
+pub(super) fn validate_path(path: &str) -> Result<&str, Box<dyn Error>> {
     let path = Path::new(path);
+    // only plain names keep the path within the note stack
+    if path
+        .components()
+        .any(|c| !matches!(c, Component::Normal(_)))
+    {
+        return Err(format!("'{}' is not a path within the note stack", path.display()).into());
+    }
     path.iter()
         .try_for_each(|s: &OsStr| -> Result<(), Box<dyn Error>> {
             let st = s.to_str().ok_or(format!(
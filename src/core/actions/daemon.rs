@@ -0,0 +1,171 @@
+use std::{
+    collections::HashMap,
+    error::Error,
+    io::{BufRead, BufReader, Write},
+    os::unix::net::{UnixListener, UnixStream},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    core::{index::Index, markdown::NotesFrontMatter},
+    system::Configuration,
+};
+
+use super::{Command, Commands};
+
+const SOCKET: &str = "notes.sock";
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A note as served by the daemon
+#[derive(Deserialize, Serialize)]
+pub(super) struct IndexedNote {
+    pub(super) path: PathBuf,
+    pub(super) frontmatter: NotesFrontMatter,
+    pub(super) offset: usize,
+}
+
+/// Representation of the background daemon that keeps an index of every note stack
+pub struct DaemonCommand {
+    stacks: PathBuf,
+    socket: PathBuf,
+    interval: Duration,
+}
+
+impl Command<'_> for DaemonCommand {
+    fn new(args: Commands, conf: &Configuration) -> Result<Self, Box<dyn Error>> {
+        let Commands::Daemon { interval } = args else {
+            unreachable!("Non-daemon command passed to daemon handler.");
+        };
+
+        let root = Path::new(&conf.settings.path);
+        Ok(Self {
+            stacks: PathBuf::from(
+                root.parent()
+                    .ok_or("unable to fetch parent for daemon command")?,
+            ),
+            socket: socket(root).ok_or("unable to resolve the daemon socket")?,
+            interval: Duration::from_secs(interval),
+        })
+    }
+
+    fn execute(self) -> Result<(), Box<dyn Error>> {
+        if self.socket.try_exists()? {
+            if UnixStream::connect(&self.socket).is_ok() {
+                return Err(format!(
+                    "a daemon is already listening on '{}'",
+                    self.socket.display()
+                )
+                .into());
+            }
+            // left behind by a daemon that did not shut down cleanly
+            std::fs::remove_file(&self.socket)?;
+        }
+
+        let indexes = Arc::new(Mutex::new(HashMap::<PathBuf, Index>::new()));
+        watch(&self.stacks, &indexes)?;
+
+        let listener = UnixListener::bind(&self.socket)?;
+        println!("listening on {}", self.socket.display());
+
+        let watched = Arc::clone(&indexes);
+        let (stacks, interval) = (self.stacks.clone(), self.interval);
+        std::thread::spawn(move || {
+            loop {
+                std::thread::sleep(interval);
+                if let Err(err) = watch(&stacks, &watched) {
+                    eprintln!("daemon: failed to watch note stacks: {err}");
+                }
+            }
+        });
+
+        for stream in listener.incoming() {
+            if let Err(err) = stream
+                .map_err(|e| e.into())
+                .and_then(|s| respond(s, &indexes))
+            {
+                eprintln!("daemon: {err}");
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Location of the daemon socket within the notes data directory
+fn socket(root: &Path) -> Option<PathBuf> {
+    Some(root.parent()?.parent()?.join(SOCKET))
+}
+
+/// Refreshes the index of every note stack, picking up any changed, created or removed notes
+fn watch(stacks: &Path, indexes: &Mutex<HashMap<PathBuf, Index>>) -> Result<(), Box<dyn Error>> {
+    let mut indexes = indexes.lock().map_err(|_| "daemon index lock poisoned")?;
+    let mut found = Vec::new();
+    for child in std::fs::read_dir(stacks)? {
+        let path = child?.path();
+        if path.is_dir() && !path.file_name().unwrap().to_string_lossy().starts_with(".") {
+            let index = indexes
+                .entry(path.clone())
+                .or_insert_with(|| Index::load(&path));
+            // the cache is kept warm for when the daemon is no longer running
+            if let Err(err) = index
+                .refresh(&path)
+                .and_then(|changed| if changed { index.save(&path) } else { Ok(()) })
+            {
+                eprintln!("daemon: failed to refresh index: {err}");
+            }
+            found.push(path);
+        }
+    }
+    indexes.retain(|p, _| found.contains(p));
+    Ok(())
+}
+
+/// Answers a single query, which is the path of the note stack to be listed. Queries are
+/// answered from the indexes kept up to date by the watcher, and only for watched note stacks.
+fn respond(
+    stream: UnixStream,
+    indexes: &Mutex<HashMap<PathBuf, Index>>,
+) -> Result<(), Box<dyn Error>> {
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    let mut root = String::new();
+    BufReader::new(&stream).read_line(&mut root)?;
+    if root.trim_end().is_empty() {
+        // liveness check from a daemon that is starting up
+        return Ok(());
+    }
+    let root = PathBuf::from(root.trim_end());
+
+    let notes = {
+        let indexes = indexes.lock().map_err(|_| "daemon index lock poisoned")?;
+        // closing the connection unanswered makes the client walk the note stack itself
+        let index = indexes
+            .get(&root)
+            .ok_or_else(|| format!("'{}' is not a watched note stack", root.display()))?;
+        index
+            .entries
+            .iter()
+            .map(|(path, entry)| IndexedNote {
+                path: path.clone(),
+                frontmatter: entry.frontmatter.clone(),
+                offset: entry.offset,
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let mut writer = std::io::BufWriter::new(&stream);
+    serde_json::to_writer(&mut writer, &notes)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Queries a running daemon for the notes of a note stack. `None` is returned when no
+/// daemon is running so that callers can fall back to walking the note stack.
+pub(super) fn query(root: &Path) -> Option<Vec<IndexedNote>> {
+    let mut stream = UnixStream::connect(socket(root)?).ok()?;
+    stream.set_read_timeout(Some(TIMEOUT)).ok()?;
+    writeln!(stream, "{}", root.display()).ok()?;
+    serde_json::from_reader(BufReader::new(stream)).ok()
+}
@@ -0,0 +1,28 @@
+use std::{error::Error, path::PathBuf};
+
+use crate::core::{editor, resolve};
+
+use super::{Command, Commands, Configuration};
+
+/// Representation of an edit command used to reopen an existing note
+pub struct EditCommand<'a> {
+    path: PathBuf,
+    editor: &'a str,
+}
+
+impl<'a> Command<'a> for EditCommand<'a> {
+    fn new(args: Commands, conf: &'a Configuration) -> Result<Self, Box<dyn Error>> {
+        let Commands::Edit { note } = args else {
+            unreachable!("Non-edit command provided to edit handler.")
+        };
+
+        Ok(Self {
+            path: resolve::note(conf.settings.path.as_ref(), &note)?,
+            editor: editor::resolve(conf),
+        })
+    }
+
+    fn execute(self) -> Result<(), Box<dyn Error>> {
+        editor::open(self.editor, &self.path)
+    }
+}
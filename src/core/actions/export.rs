@@ -0,0 +1,401 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, BinaryHeap, HashSet},
+    error::Error,
+    io::Read,
+    path::{Component, Path, PathBuf},
+    sync::LazyLock,
+};
+
+use chrono::{DateTime, Local};
+use pulldown_cmark::{Event, Options, Parser, Tag};
+use regex::{Captures, Regex};
+
+use crate::{
+    cli::{Commands, Export},
+    core::resolve::{self, Catalog},
+    system::Configuration,
+};
+
+use super::{
+    Command,
+    list::{ListEntry, Visibility, root_bfs_walk},
+};
+
+const STYLE: &str = "body{font-family:sans-serif;max-width:48rem;margin:2rem auto;padding:0 1rem;line-height:1.5}\
+nav{margin-bottom:2rem}.meta{color:#666}.unresolved{color:#a00}pre{overflow-x:auto}";
+
+static WIKI_LINK: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\[\[([^\]|#]+)(?:#[^\]|]*)?(?:\|([^\]]+))?\]\]")
+        .expect("wiki link pattern is valid")
+});
+
+/// A note to be exported, with paths relative to the root of the note stack
+struct Page {
+    source: PathBuf,
+    title: String,
+    date: String,
+    categories: Vec<String>,
+    tags: Vec<String>,
+    body: String,
+}
+
+impl Page {
+    /// Location of the rendered note relative to the output directory
+    fn output(&self) -> PathBuf {
+        Path::new("notes").join(self.source.with_extension("html"))
+    }
+}
+
+/// Representation of a command that exports a note stack as a static html site
+pub struct ExportCommand {
+    root: PathBuf,
+    out: PathBuf,
+}
+
+impl Command<'_> for ExportCommand {
+    fn new(args: Commands, conf: &Configuration) -> Result<Self, Box<dyn Error>> {
+        let Commands::Export {
+            export: Export::Html { outdir },
+        } = args
+        else {
+            unreachable!("Non-export command passed to export handler.");
+        };
+
+        let root = PathBuf::from(&conf.settings.path);
+        if resolve::normalise(&std::path::absolute(&outdir)?)
+            .starts_with(resolve::normalise(&std::path::absolute(&root)?))
+        {
+            return Err("the export directory cannot be within the note stack".into());
+        }
+        std::fs::create_dir_all(&outdir)?;
+
+        Ok(Self { root, out: outdir })
+    }
+
+    fn execute(self) -> Result<(), Box<dyn Error>> {
+        let mut entries = BinaryHeap::<ListEntry>::new();
+        root_bfs_walk(&self.root, None, Visibility::Visible, &mut entries)?;
+
+        let mut pages = Vec::new();
+        for entry in entries.into_sorted_vec().into_iter().rev() {
+            let mut body = String::new();
+            entry.body()?.read_to_string(&mut body)?;
+            let metadata = entry.frontmatter.notes_metadata;
+            let mut categories = Vec::from_iter(metadata.category);
+            categories.extend(metadata.subcategories.unwrap_or_default());
+            // categories become directories of the site, so they may not lead outside of it
+            if !categories.iter().all(|category| {
+                let mut components = Path::new(category).components();
+                matches!(
+                    (components.next(), components.next()),
+                    (Some(Component::Normal(_)), None)
+                )
+            }) {
+                eprintln!(
+                    "{}: invalid category '{}', exporting the note without one",
+                    resolve::relative(&self.root, &entry.path),
+                    categories.join("/")
+                );
+                categories.clear();
+            }
+            pages.push(Page {
+                source: PathBuf::from(resolve::relative(&self.root, &entry.path)),
+                title: entry.frontmatter.title,
+                date: entry.frontmatter.date,
+                categories,
+                tags: entry.frontmatter.tags.unwrap_or_default(),
+                body,
+            });
+        }
+
+        let catalog = Catalog::new(&self.root)?;
+        let visible = HashSet::<&Path>::from_iter(pages.iter().map(|p| p.source.as_path()));
+        for page in &pages {
+            let body = self.render(page, &catalog, &visible);
+            self.write(&page.output(), &page.title, &note(page, &body))?;
+        }
+
+        // every category along with its ancestors so that each level has an index
+        let mut categories = BTreeMap::<Vec<String>, Vec<&Page>>::new();
+        // the root index is the home page even when every note has a category
+        categories.entry(Vec::new()).or_default();
+        for page in &pages {
+            for depth in 0..page.categories.len() {
+                categories
+                    .entry(page.categories[..depth].to_vec())
+                    .or_default();
+            }
+            categories
+                .entry(page.categories.clone())
+                .or_default()
+                .push(page);
+        }
+        let mut tags = BTreeMap::<&str, Vec<&Page>>::new();
+        for page in &pages {
+            for tag in &page.tags {
+                tags.entry(tag).or_default().push(page);
+            }
+        }
+
+        for (path, notes) in &categories {
+            let output = category_output(path);
+            let children = categories
+                .keys()
+                .filter(|c| c.len() == path.len() + 1 && c.starts_with(path))
+                .collect::<BTreeSet<_>>();
+            let mut content = String::new();
+            if !path.is_empty() {
+                content += &format!("<h1>{}</h1>\n", escape(&path.join("/")));
+            } else {
+                content += "<h1>Notes</h1>\n";
+            }
+            if !children.is_empty() {
+                content += "<h2>Categories</h2>\n<ul>\n";
+                for child in children {
+                    content += &format!(
+                        "<li><a href=\"{}\">{}</a></li>\n",
+                        href(&output, &category_output(child)),
+                        escape(child.last().unwrap())
+                    );
+                }
+                content += "</ul>\n";
+            }
+            if !notes.is_empty() {
+                content += "<h2>Notes</h2>\n";
+                content += &listing(&output, notes);
+            }
+            let title = if path.is_empty() {
+                "Notes".to_owned()
+            } else {
+                path.join("/")
+            };
+            self.write(&output, &title, &content)?;
+        }
+
+        let output = Path::new("tags/index.html");
+        let mut content = String::from("<h1>Tags</h1>\n<ul>\n");
+        for (tag, notes) in &tags {
+            content += &format!(
+                "<li><a href=\"{}\">{}</a> ({})</li>\n",
+                href(output, &tag_output(tag)),
+                escape(tag),
+                notes.len()
+            );
+        }
+        content += "</ul>\n";
+        self.write(output, "Tags", &content)?;
+        for (tag, notes) in &tags {
+            let output = tag_output(tag);
+            let content = format!("<h1>#{}</h1>\n{}", escape(tag), listing(&output, notes));
+            self.write(&output, tag, &content)?;
+        }
+
+        println!(
+            "exported {} note(s) to '{}'",
+            pages.len(),
+            self.out.display()
+        );
+        Ok(())
+    }
+}
+
+impl ExportCommand {
+    /// Renders the body of a note to html, rewriting links between notes to their pages
+    fn render(&self, page: &Page, catalog: &Catalog, visible: &HashSet<&Path>) -> String {
+        let source = self.root.join(&page.source);
+        let mut fenced = false;
+        let body = Vec::from_iter(page.body.lines().map(|line| {
+            let trimmed = line.trim_start();
+            if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+                fenced = !fenced;
+            }
+            if fenced {
+                return line.to_owned();
+            }
+            WIKI_LINK
+                .replace_all(line, |captures: &Captures| {
+                    let target = captures[1].trim();
+                    let text = captures
+                        .get(2)
+                        .map_or(target, |alias| alias.as_str().trim());
+                    match catalog
+                        .link(&source, target)
+                        .map(|p| PathBuf::from(resolve::relative(&self.root, p)))
+                        .filter(|p| visible.contains(p.as_path()))
+                    {
+                        Some(target) => format!(
+                            "[{text}]({})",
+                            href(
+                                &page.output(),
+                                &Path::new("notes").join(target.with_extension("html"))
+                            )
+                        ),
+                        None => format!("<span class=\"unresolved\">{}</span>", escape(text)),
+                    }
+                })
+                .into_owned()
+        }))
+        .join("\n");
+
+        let directory = page.source.parent().unwrap_or(Path::new(""));
+        let options = Options::ENABLE_TABLES
+            | Options::ENABLE_FOOTNOTES
+            | Options::ENABLE_STRIKETHROUGH
+            | Options::ENABLE_TASKLISTS;
+        let parser = Parser::new_ext(&body, options).map(|event| match event {
+            Event::Start(Tag::Link {
+                link_type,
+                dest_url,
+                title,
+                id,
+            }) => Event::Start(Tag::Link {
+                link_type,
+                dest_url: rewrite(directory, &dest_url, visible).into(),
+                title,
+                id,
+            }),
+            event => event,
+        });
+        let mut html = String::new();
+        pulldown_cmark::html::push_html(&mut html, parser);
+        html
+    }
+
+    /// Writes a page within the output directory
+    fn write(&self, output: &Path, title: &str, content: &str) -> Result<(), Box<dyn Error>> {
+        let path = self.out.join(output);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, layout(output, title, content))?;
+        Ok(())
+    }
+}
+
+/// Rewrites relative markdown links to exported notes so that they point at their pages
+fn rewrite(directory: &Path, url: &str, visible: &HashSet<&Path>) -> String {
+    if url.starts_with(['/', '#']) || url.contains(':') {
+        return url.to_owned();
+    }
+    let (path, anchor) = url
+        .split_once('#')
+        .map_or((url, None), |(p, a)| (p, Some(a)));
+    let target = resolve::normalise(&directory.join(path.replace("%20", " ")));
+    if target.extension().is_none_or(|ext| ext != "md") || !visible.contains(target.as_path()) {
+        return url.to_owned();
+    }
+    let mut url = Path::new(path)
+        .with_extension("html")
+        .to_string_lossy()
+        .to_string();
+    if let Some(anchor) = anchor {
+        url += "#";
+        url += anchor;
+    }
+    url
+}
+
+fn category_output(path: &[String]) -> PathBuf {
+    if path.is_empty() {
+        PathBuf::from("index.html")
+    } else {
+        Path::new("categories")
+            .join(path.join("/"))
+            .join("index.html")
+    }
+}
+
+fn tag_output(tag: &str) -> PathBuf {
+    // every other byte is escaped so that distinct tags never share a page
+    let mut name = String::new();
+    for byte in tag.bytes() {
+        match byte {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' => name.push(byte as char),
+            _ => name += &format!("_{byte:02X}"),
+        }
+    }
+    Path::new("tags").join(format!("{name}.html"))
+}
+
+/// Relative url from one page to another, both relative to the output directory
+fn href(from: &Path, to: &Path) -> String {
+    let depth = from.parent().map_or(0, |p| p.components().count());
+    let url = "../".repeat(depth) + &to.to_string_lossy();
+    url.replace(' ', "%20")
+}
+
+/// Html list of notes linking to their pages
+fn listing(output: &Path, notes: &[&Page]) -> String {
+    let mut html = String::from("<ul>\n");
+    for page in notes {
+        html += &format!(
+            "<li><a href=\"{}\">{}</a> <span class=\"meta\">{}</span></li>\n",
+            href(output, &page.output()),
+            escape(&page.title),
+            escape(&display_date(&page.date))
+        );
+    }
+    html += "</ul>\n";
+    html
+}
+
+/// Html of a note page with its metadata ahead of the rendered body
+fn note(page: &Page, body: &str) -> String {
+    let output = page.output();
+    let mut meta = vec![escape(&display_date(&page.date))];
+    if !page.categories.is_empty() {
+        meta.push(format!(
+            "<a href=\"{}\">{}</a>",
+            href(&output, &category_output(&page.categories)),
+            escape(&page.categories.join("/"))
+        ));
+    }
+    for tag in &page.tags {
+        meta.push(format!(
+            "<a href=\"{}\">#{}</a>",
+            href(&output, &tag_output(tag)),
+            escape(tag)
+        ));
+    }
+    format!(
+        "<h1>{}</h1>\n<p class=\"meta\">{}</p>\n{body}",
+        escape(&page.title),
+        meta.join(" · ")
+    )
+}
+
+fn display_date(date: &str) -> String {
+    date.parse::<DateTime<Local>>()
+        .map(|dt| dt.format("%d-%b-%Y").to_string())
+        .unwrap_or_else(|_| date.to_owned())
+}
+
+fn layout(output: &Path, title: &str, content: &str) -> String {
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>{}</title>
+<style>{STYLE}</style>
+</head>
+<body>
+<nav><a href="{}">home</a> · <a href="{}">tags</a></nav>
+<main>
+{content}</main>
+</body>
+</html>
+"#,
+        escape(title),
+        href(output, Path::new("index.html")),
+        href(output, Path::new("tags/index.html")),
+    )
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
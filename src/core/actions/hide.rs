@@ -0,0 +1,50 @@
+use std::{error::Error, path::PathBuf};
+
+use crate::core::{frontmatter, resolve};
+
+use super::{Command, Commands, Configuration};
+
+/// Representation of a command that hides or shows notes when listing
+pub struct HideCommand {
+    root: PathBuf,
+    paths: Vec<PathBuf>,
+    hidden: bool,
+}
+
+impl Command<'_> for HideCommand {
+    fn new(args: Commands, conf: &Configuration) -> Result<Self, Box<dyn Error>> {
+        let (notes, hidden) = match args {
+            Commands::Hide { notes } => (notes, true),
+            Commands::Unhide { notes } => (notes, false),
+            _ => unreachable!("Non-hide command passed to hide handler."),
+        };
+
+        let root = PathBuf::from(&conf.settings.path);
+        Ok(Self {
+            paths: notes
+                .iter()
+                .map(|note| resolve::note(&root, note))
+                .collect::<Result<_, _>>()?,
+            root,
+            hidden,
+        })
+    }
+
+    fn execute(self) -> Result<(), Box<dyn Error>> {
+        let state = if self.hidden { "hidden" } else { "visible" };
+        for path in self.paths {
+            let mut changed = false;
+            frontmatter::update(&path, |fm| {
+                changed = fm.notes_metadata.hidden != self.hidden;
+                fm.notes_metadata.hidden = self.hidden;
+            })?;
+            let name = resolve::relative(&self.root, &path);
+            if changed {
+                println!("'{name}' is now {state}");
+            } else {
+                println!("'{name}' is already {state}");
+            }
+        }
+        Ok(())
+    }
+}
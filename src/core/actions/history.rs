@@ -0,0 +1,237 @@
+use std::{
+    error::Error,
+    io::{IsTerminal, Write},
+    path::PathBuf,
+};
+
+use chrono::{DateTime, Local};
+use git2::{Commit, Patch, Repository};
+use termcolor::{Color, StandardStream, WriteColor};
+
+use crate::{
+    core::{git, resolve},
+    system::Configuration,
+    write_coloured, write_colouredln,
+};
+
+use super::{Command, Commands};
+
+const HEAD: &str = "HEAD";
+
+enum Operation {
+    History,
+    Show(String),
+    Diff(Option<String>),
+    Restore(Option<String>, bool),
+}
+
+/// Representation of a command that reads the saved versions of a note from the git
+/// repository of the note stack
+pub struct HistoryCommand {
+    root: PathBuf,
+    // relative to the root of the note stack, as tracked by git
+    path: PathBuf,
+    operation: Operation,
+}
+
+impl Command<'_> for HistoryCommand {
+    fn new(args: Commands, conf: &Configuration) -> Result<Self, Box<dyn Error>> {
+        let (note, operation) = match args {
+            Commands::History { note } => (note, Operation::History),
+            Commands::Show { note } => match note.rsplit_once('@') {
+                Some((note, rev)) => (note.to_owned(), Operation::Show(rev.to_owned())),
+                None => (note, Operation::Show(HEAD.to_owned())),
+            },
+            Commands::Diff { note, rev } => (note, Operation::Diff(rev)),
+            Commands::Restore { note, rev, force } => (note, Operation::Restore(rev, force)),
+            _ => unreachable!("Non-history command passed to history handler."),
+        };
+
+        let root = PathBuf::from(&conf.settings.path);
+        let repo = git::repository(&root)?;
+        let path = match resolve::note(&root, &note) {
+            Ok(path) => PathBuf::from(resolve::relative(&root, &path)),
+            Err(err) => {
+                // deleted notes are only known to the history of the note stack
+                let path = PathBuf::from(match note.ends_with(".md") {
+                    true => note,
+                    false => format!("{note}.md"),
+                });
+                if git::history(&repo, &path)?.is_empty() {
+                    return Err(err);
+                }
+                path
+            }
+        };
+
+        Ok(Self {
+            root,
+            path,
+            operation,
+        })
+    }
+
+    fn execute(self) -> Result<(), Box<dyn Error>> {
+        let repo = git::repository(&self.root)?;
+        match &self.operation {
+            Operation::History => self.history(&repo),
+            Operation::Show(rev) => {
+                let commit = git::revision(&repo, rev)?;
+                print!("{}", self.saved(&repo, &commit, rev)?);
+                Ok(())
+            }
+            Operation::Diff(rev) => self.diff(&repo, rev.as_deref().unwrap_or(HEAD)),
+            Operation::Restore(rev, force) => self.restore(&repo, rev.as_deref(), *force),
+        }
+    }
+}
+
+impl HistoryCommand {
+    /// Lists every commit that changed the note, newest first
+    fn history(&self, repo: &Repository) -> Result<(), Box<dyn Error>> {
+        let revisions = git::history(repo, &self.path)?;
+        if revisions.is_empty() {
+            return Err(format!("'{}' has not been saved yet", self.path.display()).into());
+        }
+        let mut out = StandardStream::stdout(termcolor::ColorChoice::Always);
+        for revision in revisions {
+            let commit = &revision.commit;
+            write_coloured!(
+                out,
+                colour = Color::Yellow,
+                "{:.7}",
+                commit.id().to_string()
+            );
+            write_coloured!(out, bold, " {}", date(commit));
+            write!(out, " {}", commit.summary().unwrap_or_default())?;
+            if revision.path != self.path {
+                write_coloured!(out, colour = Color::Green, " ({})", revision.path.display());
+            }
+            if revision.deleted {
+                write_coloured!(out, colour = Color::Red, " (deleted)");
+            }
+            writeln!(out)?;
+        }
+        Ok(())
+    }
+
+    /// Shows the changes made to the note since a revision as a unified diff
+    fn diff(&self, repo: &Repository, rev: &str) -> Result<(), Box<dyn Error>> {
+        let commit = git::revision(repo, rev)?;
+        let old = self.saved(repo, &commit, rev)?;
+        let new = std::fs::read_to_string(self.root.join(&self.path)).unwrap_or_default();
+        let mut patch = Patch::from_buffers(
+            old.as_bytes(),
+            Some(&self.path),
+            new.as_bytes(),
+            Some(&self.path),
+            None,
+        )?;
+
+        let mut lines = Vec::new();
+        patch.print(&mut |_, _, line| {
+            let content = String::from_utf8_lossy(line.content()).into_owned();
+            lines.push((line.origin(), content));
+            true
+        })?;
+
+        let mut out = StandardStream::stdout(termcolor::ColorChoice::Always);
+        for (origin, content) in lines {
+            match origin {
+                '+' => write_coloured!(out, colour = Color::Green, "+{content}"),
+                '-' => write_coloured!(out, colour = Color::Red, "-{content}"),
+                ' ' => write!(out, " {content}")?,
+                'H' => write_coloured!(out, colour = Color::Cyan, "{content}"),
+                'F' => write_coloured!(out, bold, "{content}"),
+                _ => write!(out, "{content}")?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes a saved version of the note back into the note stack
+    fn restore(
+        &self,
+        repo: &Repository,
+        rev: Option<&str>,
+        force: bool,
+    ) -> Result<(), Box<dyn Error>> {
+        let (contents, id) = match rev {
+            Some(rev) => {
+                let commit = git::revision(repo, rev)?;
+                (self.saved(repo, &commit, rev)?, commit.id())
+            }
+            None => {
+                // the newest version is the last one that was not a deletion
+                let revisions = git::history(repo, &self.path)?;
+                let revision = revisions
+                    .iter()
+                    .find(|revision| !revision.deleted)
+                    .ok_or(format!("'{}' has not been saved yet", self.path.display()))?;
+                let contents = git::file_at(repo, &revision.commit.tree()?, &revision.path)?
+                    .unwrap_or_default();
+                (contents, revision.commit.id())
+            }
+        };
+
+        let path = self.root.join(&self.path);
+        // unsaved edits were never committed and could not be recovered once overwritten
+        if !force
+            && let Ok(current) = std::fs::read_to_string(&path)
+            && current != contents
+            && git::committed_file(repo, &self.path)?.is_none_or(|saved| saved != current)
+        {
+            return Err(format!(
+                "'{}' has unsaved changes; save them first or restore with --force",
+                self.path.display()
+            )
+            .into());
+        }
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, contents)?;
+        let mut out = StandardStream::stdout(termcolor::ColorChoice::Always);
+        write!(out, "restored ")?;
+        write_coloured!(out, colour = Color::Green, "{}", self.path.display());
+        write!(out, " from ")?;
+        write_colouredln!(out, colour = Color::Yellow, "{:.7}", id.to_string());
+        Ok(())
+    }
+
+    /// Contents of the note as of a commit, following the note across moves
+    fn saved(
+        &self,
+        repo: &Repository,
+        commit: &Commit,
+        rev: &str,
+    ) -> Result<String, Box<dyn Error>> {
+        let tree = commit.tree()?;
+        if let Some(contents) = git::file_at(repo, &tree, &self.path)? {
+            return Ok(contents);
+        }
+        for revision in git::history(repo, &self.path)? {
+            let id = revision.commit.id();
+            if id == commit.id() || repo.graph_descendant_of(commit.id(), id)? {
+                if let Some(contents) = git::file_at(repo, &tree, &revision.path)?
+                    && !revision.deleted
+                {
+                    return Ok(contents);
+                }
+                break;
+            }
+        }
+        Err(format!("'{}' does not exist at {rev}", self.path.display()).into())
+    }
+}
+
+/// Date of a commit in the local timezone
+fn date(commit: &Commit) -> String {
+    DateTime::from_timestamp(commit.time().seconds(), 0)
+        .map(|date| {
+            date.with_timezone(&Local)
+                .format("%d-%b-%Y %H:%M:%S")
+                .to_string()
+        })
+        .unwrap_or_default()
+}
@@ -0,0 +1,228 @@
+use std::{
+    error::Error,
+    fs::OpenOptions,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::LazyLock,
+};
+
+use chrono::{DateTime, Local, NaiveDate};
+use regex::Regex;
+use serde_yaml_ng::{Mapping, Value};
+
+use crate::{
+    core::{
+        frontmatter::{self, Document},
+        links, markdown,
+        markdown::NotesFrontMatter,
+        resolve,
+    },
+    system::Configuration,
+};
+
+use super::{Command, Commands};
+
+static HASHTAG: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?:^|\s)#([\w/-]+)").expect("hashtag pattern is valid"));
+
+static INLINE_CODE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"`[^`]*`").expect("inline code pattern is valid"));
+
+/// Representation of a command that imports a folder of markdown notes into a note stack
+pub struct ImportCommand {
+    root: PathBuf,
+    source: PathBuf,
+}
+
+impl Command<'_> for ImportCommand {
+    fn new(args: Commands, conf: &Configuration) -> Result<Self, Box<dyn Error>> {
+        let Commands::Import { dir } = args else {
+            unreachable!("Non-import command passed to import handler.");
+        };
+
+        let root = PathBuf::from(&conf.settings.path);
+        let source = dir
+            .canonicalize()
+            .map_err(|err| format!("unable to import '{}': {err}", dir.display()))?;
+        if !source.is_dir() {
+            return Err(format!("'{}' is not a directory", dir.display()).into());
+        }
+        let stack = root.canonicalize()?;
+        if source.starts_with(&stack) || stack.starts_with(&source) {
+            return Err("the import directory cannot overlap with the note stack".into());
+        }
+
+        Ok(Self { root, source })
+    }
+
+    fn execute(self) -> Result<(), Box<dyn Error>> {
+        let (mut imported, mut skipped, mut failed) = (0, 0, 0);
+        for path in resolve::notes(&self.source)? {
+            let relative = path
+                .strip_prefix(&self.source)
+                .expect("notes are collected from within the import directory");
+            let destination = self.root.join(relative);
+            if destination.try_exists()? {
+                eprintln!(
+                    "skipped '{}': a note already exists at '{}'",
+                    relative.display(),
+                    destination.display()
+                );
+                skipped += 1;
+                continue;
+            }
+            match import(&path, relative, &destination) {
+                Ok(()) => imported += 1,
+                Err(err) => {
+                    eprintln!("failed to import '{}': {err}", relative.display());
+                    failed += 1;
+                }
+            }
+        }
+
+        println!(
+            "imported {imported} note(s) into '{}', skipped {skipped}",
+            self.root.display()
+        );
+        if failed > 0 {
+            return Err(format!("failed to import {failed} note(s)").into());
+        }
+        Ok(())
+    }
+}
+
+/// Copies a note into the note stack, adopting any existing front matter
+fn import(path: &Path, relative: &Path, destination: &Path) -> Result<(), Box<dyn Error>> {
+    let contents = std::fs::read_to_string(path)?;
+    let modified = DateTime::<Local>::from(std::fs::metadata(path)?.modified()?);
+    let (yaml, body, separator) = match frontmatter::split(&contents) {
+        Some((yaml, body)) => (yaml, body, ""),
+        None => ("", contents.as_str(), "\n"),
+    };
+
+    // notes that already carry valid front matter are copied as they are
+    let document = Document::parse(yaml)
+        .or_else(|_| Document::adopt(yaml, |yaml| synthesise(yaml, body, relative, modified)))?;
+
+    if let Some(parent) = destination.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut file = OpenOptions::new()
+        .create_new(true)
+        .write(true)
+        .open(destination)?;
+    write!(file, "---\n{}---\n{separator}{body}", document.to_yaml()?)?;
+    Ok(())
+}
+
+/// Synthesises front matter for a note from its existing yaml, body and location
+fn synthesise(
+    yaml: &Mapping,
+    body: &str,
+    relative: &Path,
+    modified: DateTime<Local>,
+) -> NotesFrontMatter {
+    let title = yaml
+        .get("title")
+        .and_then(Value::as_str)
+        .map(str::to_owned)
+        .or_else(|| heading(body))
+        .unwrap_or_else(|| {
+            markdown::title(&relative.file_stem().unwrap_or_default().to_string_lossy())
+        });
+
+    let date = yaml
+        .get("date")
+        .and_then(Value::as_str)
+        .and_then(date)
+        .unwrap_or_else(|| modified.to_rfc3339());
+
+    let mut tags = Vec::new();
+    for tag in yaml
+        .get("tags")
+        .map(listed)
+        .unwrap_or_default()
+        .into_iter()
+        .chain(hashtags(body))
+    {
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+
+    let category = relative
+        .parent()
+        .map(|p| p.to_string_lossy().to_string())
+        .filter(|p| !p.is_empty());
+
+    NotesFrontMatter::new(
+        title,
+        category.as_deref(),
+        (!tags.is_empty()).then_some(tags),
+        date,
+    )
+}
+
+/// Text of the first heading in the body of a note
+fn heading(body: &str) -> Option<String> {
+    links::lines(body).find_map(|(_, line)| {
+        let text = line.trim().strip_prefix('#')?.trim_start_matches('#');
+        let text = text
+            .strip_prefix([' ', '\t'])?
+            .trim()
+            .trim_end_matches('#')
+            .trim_end();
+        (!text.is_empty()).then(|| text.to_owned())
+    })
+}
+
+/// Normalises a date to rfc3339, treating plain dates as the start of the day
+fn date(date: &str) -> Option<String> {
+    if let Ok(date) = DateTime::parse_from_rfc3339(date) {
+        return Some(date.to_rfc3339());
+    }
+    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .ok()?
+        .and_hms_opt(0, 0, 0)?
+        .and_local_timezone(Local)
+        .earliest()
+        .map(|date| date.to_rfc3339())
+}
+
+/// Tags listed in yaml either as a sequence or as a comma or space separated string
+fn listed(tags: &Value) -> Vec<String> {
+    let tags = match tags {
+        Value::Sequence(tags) => Vec::from_iter(tags.iter().filter_map(|tag| match tag {
+            Value::String(tag) => Some(tag.clone()),
+            Value::Number(tag) => Some(tag.to_string()),
+            _ => None,
+        })),
+        Value::String(tags) => Vec::from_iter(tags.split([',', ' ']).map(str::to_owned)),
+        _ => Vec::new(),
+    };
+    Vec::from_iter(
+        tags.iter()
+            .map(|tag| {
+                tag.trim()
+                    .trim_matches(['"', '\''])
+                    .trim_start_matches('#')
+                    .to_owned()
+            })
+            .filter(|tag| !tag.is_empty()),
+    )
+}
+
+/// Inline `#tags` within the body of a note, ignoring code and purely numeric tags
+fn hashtags(body: &str) -> Vec<String> {
+    let mut tags = Vec::new();
+    for (_, line) in links::lines(body) {
+        let line = INLINE_CODE.replace_all(line, "");
+        for capture in HASHTAG.captures_iter(&line) {
+            let tag = capture[1].trim_end_matches(['/', '-']);
+            if tag.chars().any(|c| !c.is_ascii_digit()) {
+                tags.push(tag.to_owned());
+            }
+        }
+    }
+    tags
+}
@@ -0,0 +1,81 @@
+use std::{error::Error, path::PathBuf};
+
+use chrono::{Days, Local, NaiveDate};
+
+use crate::core::editor;
+
+use super::{Command, Commands, Configuration, create::CreateCommand};
+
+pub(super) const DEFAULT_JOURNAL_CATEGORY: &str = "journal";
+
+/// Representation of a command that opens the journal note of a day, creating it if needed
+pub struct JournalCommand<'a> {
+    conf: &'a Configuration,
+    path: PathBuf,
+    category: String,
+    name: String,
+    quiet: bool,
+}
+
+impl<'a> Command<'a> for JournalCommand<'a> {
+    fn new(args: Commands, conf: &'a Configuration) -> Result<Self, Box<dyn Error>> {
+        let (date, quiet) = match args {
+            Commands::Today { yesterday, quiet } => {
+                let today = Local::now().date_naive();
+                let date = if yesterday {
+                    today
+                        .checked_sub_days(Days::new(1))
+                        .ok_or("unable to compute yesterday's date")?
+                } else {
+                    today
+                };
+                (date, quiet)
+            }
+            Commands::Day { date, quiet } => (
+                NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+                    .map_err(|err| format!("invalid date '{date}', expected YYYY-MM-DD: {err}"))?,
+                quiet,
+            ),
+            _ => unreachable!("Non-journal command passed to journal handler."),
+        };
+
+        let category = conf
+            .options
+            .as_ref()
+            .and_then(|o| o.journal_category.clone())
+            .unwrap_or_else(|| DEFAULT_JOURNAL_CATEGORY.to_owned());
+        let name = date.format("%Y-%m-%d").to_string();
+
+        Ok(Self {
+            conf,
+            path: PathBuf::from(&conf.settings.path)
+                .join(&category)
+                .join(format!("{name}.md")),
+            category,
+            name,
+            quiet,
+        })
+    }
+
+    fn execute(self) -> Result<(), Box<dyn Error>> {
+        if self.path.try_exists()? {
+            if !self.quiet {
+                editor::open(editor::resolve(self.conf), &self.path)?;
+            }
+            return Ok(());
+        }
+
+        CreateCommand::new(
+            Commands::Create {
+                quiet: self.quiet,
+                category: Some(self.category),
+                name: self.name,
+                tags: None,
+                template: None,
+                no_template: false,
+            },
+            self.conf,
+        )?
+        .execute()
+    }
+}
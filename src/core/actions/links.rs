@@ -0,0 +1,84 @@
+use std::{
+    error::Error,
+    io::{IsTerminal, Write},
+    path::PathBuf,
+};
+
+use termcolor::{Color, StandardStream, WriteColor};
+
+use crate::{
+    core::{
+        links,
+        resolve::{self, Catalog},
+    },
+    system::Configuration,
+    write_coloured,
+};
+
+use super::{Command, Commands};
+
+enum LinkDirection {
+    Outgoing,
+    Incoming,
+}
+
+/// Representation of a command listing the links from or to a note
+pub struct LinksCommand {
+    root: PathBuf,
+    path: PathBuf,
+    direction: LinkDirection,
+}
+
+impl Command<'_> for LinksCommand {
+    fn new(args: Commands, conf: &Configuration) -> Result<Self, Box<dyn Error>> {
+        let (note, direction) = match args {
+            Commands::Links { note } => (note, LinkDirection::Outgoing),
+            Commands::Backlinks { note } => (note, LinkDirection::Incoming),
+            _ => unreachable!("Non-links command passed to links handler."),
+        };
+
+        let root = PathBuf::from(&conf.settings.path);
+        Ok(Self {
+            path: resolve::note(&root, &note)?,
+            root,
+            direction,
+        })
+    }
+
+    fn execute(self) -> Result<(), Box<dyn Error>> {
+        let catalog = Catalog::new(&self.root)?;
+        let mut out = StandardStream::stdout(termcolor::ColorChoice::Always);
+        match self.direction {
+            LinkDirection::Outgoing => {
+                let (body, offset) = links::body(&self.path)?;
+                for link in links::wiki(&body, offset) {
+                    write_coloured!(out, colour = Color::Yellow, "{}", link.line);
+                    write!(out, ": [[{}]] ", link.target)?;
+                    match catalog.link(&self.path, &link.target) {
+                        Some(path) => {
+                            write_coloured!(out, colour = Color::Green, "{}", path.display());
+                        }
+                        None => {
+                            write_coloured!(out, colour = Color::Red, "(unresolved)");
+                        }
+                    }
+                    writeln!(out)?;
+                }
+            }
+            LinkDirection::Incoming => {
+                for note in catalog.notes() {
+                    let (body, offset) = links::body(note)?;
+                    for link in links::wiki(&body, offset) {
+                        if catalog.link(note, &link.target) == Some(self.path.as_path()) {
+                            write_coloured!(out, colour = Color::Green, "{}", note.display());
+                            write!(out, ":")?;
+                            write_coloured!(out, colour = Color::Yellow, "{}", link.line);
+                            writeln!(out, ": [[{}]]", link.target)?;
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
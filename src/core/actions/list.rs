@@ -1,20 +1,23 @@
 mod handlers;
 
 use std::{
-    collections::{BinaryHeap, HashSet, VecDeque},
+    collections::{BinaryHeap, HashSet},
     error::Error,
     fs::File,
     io::BufReader,
-    io::Write,
+    io::{BufRead, Write},
     path::{Path, PathBuf},
 };
 
 use crate::{
-    core::{frontmatter, markdown::NotesFrontMatter},
+    cli::{Format, TagMatch},
+    core::{index::Index, markdown::NotesFrontMatter},
     system::Configuration,
 };
 
-use super::{Command, Commands};
+use serde::Serialize;
+
+use super::{Command, Commands, daemon};
 
 #[derive(Clone, Debug, clap::ValueEnum)]
 pub enum Opts {
@@ -25,16 +28,54 @@ pub enum Opts {
     Categories,
 }
 
-struct ListEntry {
-    path: PathBuf,
-    frontmatter: NotesFrontMatter,
-    contents: BufReader<File>,
+/// Which notes are listed based on whether they are hidden
+#[derive(Clone, Copy)]
+pub(super) enum Visibility {
+    Visible,
+    Hidden,
+    All,
+}
+
+impl Visibility {
+    fn shows(self, hidden: bool) -> bool {
+        match self {
+            Visibility::Visible => !hidden,
+            Visibility::Hidden => hidden,
+            Visibility::All => true,
+        }
+    }
+}
+
+pub(super) struct ListEntry {
+    pub(super) path: PathBuf,
+    pub(super) frontmatter: NotesFrontMatter,
+    // number of lines taken up by the front matter
+    pub(super) offset: usize,
+}
+
+impl ListEntry {
+    /// Opens the note for reading its body, skipping over the front matter
+    pub(super) fn body(&self) -> Result<BufReader<File>, Box<dyn Error>> {
+        let mut reader = BufReader::new(File::open(&self.path)?);
+        let mut line = Vec::new();
+        for _ in 0..self.offset {
+            line.clear();
+            if reader.read_until(b'\n', &mut line)? == 0 {
+                break;
+            }
+        }
+        Ok(reader)
+    }
 }
 
 pub struct ListCommand {
     path: PathBuf,
     filter: Option<String>,
     details: Option<Opts>,
+    tags: Vec<String>,
+    tag_match: TagMatch,
+    format: Option<Format>,
+    visibility: Visibility,
     entries: BinaryHeap<ListEntry>,
 }
 
@@ -70,6 +111,11 @@ impl Command<'_> for ListCommand {
             root,
             full,
             short,
+            tags,
+            tag_match,
+            format,
+            hidden,
+            all,
             category,
             categories,
             stacks,
@@ -78,11 +124,23 @@ impl Command<'_> for ListCommand {
             unreachable!("Non-list command passed to list handler.");
         };
 
+        let visibility = if all {
+            Visibility::All
+        } else if hidden {
+            Visibility::Hidden
+        } else {
+            Visibility::Visible
+        };
+
         if root {
             return Ok(Self {
                 path: PathBuf::from(&conf.settings.path),
                 filter: category,
                 details: Some(Opts::Root),
+                tags,
+                tag_match,
+                format,
+                visibility,
                 entries: BinaryHeap::<ListEntry>::new(),
             });
         } else if categories {
@@ -90,6 +148,10 @@ impl Command<'_> for ListCommand {
                 path: PathBuf::from(&conf.settings.path),
                 filter: category,
                 details: Some(Opts::Categories),
+                tags,
+                tag_match,
+                format,
+                visibility,
                 entries: BinaryHeap::<ListEntry>::new(),
             });
         } else if stacks {
@@ -101,10 +163,15 @@ impl Command<'_> for ListCommand {
                 ),
                 filter: category,
                 details: Some(Opts::Stacks),
+                tags,
+                tag_match,
+                format,
+                visibility,
                 entries: BinaryHeap::<ListEntry>::new(),
             });
         }
 
+        #[allow(clippy::collapsible_if)]
         if let Some(options) = &conf.options {
             if options.hide_root.as_ref().is_some_and(|s| s == "true") {
                 // safety: notes is a single threaded program
@@ -123,6 +190,10 @@ impl Command<'_> for ListCommand {
 
         Ok(Self {
             details,
+            tags,
+            tag_match,
+            format,
+            visibility,
             filter: category,
             path: PathBuf::from(&conf.settings.path),
             entries: BinaryHeap::<ListEntry>::new(),
@@ -132,15 +203,36 @@ impl Command<'_> for ListCommand {
     fn execute(mut self) -> Result<(), Box<dyn Error>> {
         let mut stdout = std::io::stdout().lock();
         if let Some(Opts::Stacks) = &self.details {
+            let mut stacks = Vec::new();
             for child in std::fs::read_dir(&self.path)? {
                 let path = child?.file_name();
                 if !path.to_string_lossy().starts_with(".") {
-                    writeln!(stdout, "{}", path.to_string_lossy())?;
+                    stacks.push(path.to_string_lossy().to_string());
                 }
             }
+            if let Some(format) = self.format {
+                stacks.sort();
+                let records = Vec::from_iter(stacks.iter().map(|s| StackRecord { stack: s }));
+                return handlers::formatted(&records, &["stack"], format);
+            }
+            for stack in stacks {
+                writeln!(stdout, "{}", stack)?;
+            }
             return Ok(());
         };
-        let (namelen, taglen) = root_bfs_walk(&mut self)?;
+        let (mut namelen, mut taglen) = root_bfs_walk(
+            &self.path,
+            self.filter.as_deref(),
+            self.visibility,
+            &mut self.entries,
+        )?;
+        if !self.tags.is_empty() {
+            self.entries
+                .retain(|e| has_tags(&e.frontmatter, &self.tags, self.tag_match));
+            (namelen, taglen) = self.entries.iter().try_fold((0, 0), |(n, t), e| {
+                compute_name_and_tag_widths(&e.path, &e.frontmatter, n, t)
+            })?;
+        }
         match self.details {
             Some(Opts::Short) => {
                 handlers::short(self.entries, namelen, taglen)?;
@@ -156,24 +248,36 @@ impl Command<'_> for ListCommand {
                 )?);
             }
             Some(Opts::Categories) => {
-                let set = HashSet::<&str>::from_iter(
-                    self.entries
-                        .iter()
-                        .filter(|&p| !p.frontmatter.notes_metadata.hidden)
-                        .filter_map(|p| {
-                            p.frontmatter
-                                .notes_metadata
-                                .category
-                                .as_ref()
-                                .map(|s| s.as_ref())
-                        }),
-                );
+                let set = HashSet::<&str>::from_iter(self.entries.iter().filter_map(|p| {
+                    p.frontmatter
+                        .notes_metadata
+                        .category
+                        .as_ref()
+                        .map(|s| s.as_ref())
+                }));
+                if let Some(format) = self.format {
+                    let mut categories = Vec::from_iter(set);
+                    categories.sort();
+                    let records = Vec::from_iter(
+                        categories
+                            .into_iter()
+                            .map(|category| CategoryRecord { category }),
+                    );
+                    return handlers::formatted(&records, &["category"], format);
+                }
                 for cat in set {
                     writeln!(stdout, "{}", cat)?;
                 }
                 return Ok(());
             }
-            None => handlers::default(self.entries)?,
+            None => match self.format {
+                Some(format) => handlers::records(
+                    self.entries,
+                    &self.path.file_name().unwrap_or_default().to_string_lossy(),
+                    format,
+                )?,
+                None => handlers::default(self.entries)?,
+            },
             Some(Opts::Stacks) => {
                 // no opt
             }
@@ -183,6 +287,29 @@ impl Command<'_> for ListCommand {
     }
 }
 
+#[derive(Serialize)]
+struct StackRecord<'a> {
+    stack: &'a str,
+}
+
+#[derive(Serialize)]
+struct CategoryRecord<'a> {
+    category: &'a str,
+}
+
+/// Checks whether a note has any or all of the provided tags
+pub(super) fn has_tags(
+    frontmatter: &NotesFrontMatter,
+    tags: &[String],
+    tag_match: TagMatch,
+) -> bool {
+    let note_tags = frontmatter.tags.as_deref().unwrap_or_default();
+    match tag_match {
+        TagMatch::Any => tags.iter().any(|t| note_tags.contains(t)),
+        TagMatch::All => tags.iter().all(|t| note_tags.contains(t)),
+    }
+}
+
 /// Computes padding size for tags and name
 fn compute_name_and_tag_widths(
     path: &Path,
@@ -190,10 +317,6 @@ fn compute_name_and_tag_widths(
     mut namelen: usize,
     mut taglen: usize,
 ) -> Result<(usize, usize), Box<dyn Error>> {
-    if frontmatter.notes_metadata.hidden {
-        return Ok((namelen, taglen));
-    }
-
     const PAD: usize = 2;
     let ncount = path
         .iter()
@@ -225,51 +348,73 @@ fn compute_name_and_tag_widths(
 }
 
 /// Root directory traversal that populates the list entries queue and returns padding for tags
-/// and pathnames.
-fn root_bfs_walk(list: &mut ListCommand) -> Result<(usize, usize), Box<dyn Error>> {
-    let mut dequeue = VecDeque::new();
+/// and pathnames. The index of a running daemon is used when available, otherwise the on-disk
+/// index of the note stack is refreshed so that only changed notes are parsed.
+pub(super) fn root_bfs_walk(
+    root: &Path,
+    filter: Option<&str>,
+    visibility: Visibility,
+    entries: &mut BinaryHeap<ListEntry>,
+) -> Result<(usize, usize), Box<dyn Error>> {
+    if let Some(notes) = daemon::query(root) {
+        return indexed_walk(
+            notes.into_iter().map(|n| (n.path, n.frontmatter, n.offset)),
+            filter,
+            visibility,
+            entries,
+        );
+    }
+
+    let mut index = Index::load(root);
+    if index.refresh(root)? {
+        index.save(root)?;
+    }
+    indexed_walk(
+        index
+            .entries
+            .into_iter()
+            .map(|(p, e)| (p, e.frontmatter, e.offset)),
+        filter,
+        visibility,
+        entries,
+    )
+}
+
+/// Populates the list entries queue from indexed notes without reading the notes themselves
+fn indexed_walk(
+    notes: impl Iterator<Item = (PathBuf, NotesFrontMatter, usize)>,
+    filter: Option<&str>,
+    visibility: Visibility,
+    entries: &mut BinaryHeap<ListEntry>,
+) -> Result<(usize, usize), Box<dyn Error>> {
     let mut lengths: (usize, usize) = (0, 0);
-    dequeue.push_back(list.path.clone());
-    while let Some(entry) = dequeue.pop_front() {
-        for child in std::fs::read_dir(entry)? {
-            let child = child?;
-            let mut path = child.path();
-            if path.is_dir() {
-                dequeue.push_back(path);
-            } else {
-                let path_str = path
-                    .to_str()
-                    .expect("Invalid UTF-8 sequence provided as path");
-                if path_str.is_ascii() && &path_str[path_str.len() - 2..] == "md"
-                    || path_str.chars().rev().take(2).collect::<String>() == "md"
-                {
-                    if let Some(cat) = &list.filter {
-                        path.set_extension("");
-                        if !path.to_str().unwrap().contains(cat) {
-                            continue;
-                        }
-                        path.set_extension("md");
-                    }
-                    let mut reader = BufReader::new(std::fs::File::open(&path)?);
-                    let frontmatter = frontmatter::fetch(&mut reader)?;
-                    let new_entry = ListEntry {
-                        path,
-                        frontmatter: frontmatter::generate(&frontmatter)?,
-                        contents: reader,
-                    };
-                    let (namelen, taglen) = &mut lengths;
-                    (*namelen, *taglen) = compute_name_and_tag_widths(
-                        &new_entry.path,
-                        &new_entry.frontmatter,
-                        *namelen,
-                        *taglen,
-                    )?;
-                    if !new_entry.frontmatter.notes_metadata.hidden {
-                        list.entries.push(new_entry);
-                    }
-                }
-            }
+    for (path, frontmatter, offset) in notes {
+        if let Some(cat) = filter
+            && !path.with_extension("").to_string_lossy().contains(cat)
+        {
+            continue;
         }
+        let new_entry = ListEntry {
+            path,
+            frontmatter,
+            offset,
+        };
+        push_entry(new_entry, visibility, entries, &mut lengths)?;
     }
     Ok(lengths)
 }
+
+/// Queues an entry shown by the visibility and widens the padding for tags and pathnames
+fn push_entry(
+    entry: ListEntry,
+    visibility: Visibility,
+    entries: &mut BinaryHeap<ListEntry>,
+    (namelen, taglen): &mut (usize, usize),
+) -> Result<(), Box<dyn Error>> {
+    if visibility.shows(entry.frontmatter.notes_metadata.hidden) {
+        (*namelen, *taglen) =
+            compute_name_and_tag_widths(&entry.path, &entry.frontmatter, *namelen, *taglen)?;
+        entries.push(entry);
+    }
+    Ok(())
+}
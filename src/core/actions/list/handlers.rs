@@ -6,9 +6,11 @@ use std::{
     io::{BufRead, IsTerminal, Write},
 };
 
+use serde::Serialize;
 use termcolor::{Color, StandardStream, WriteColor};
 
 use crate::{
+    cli::Format,
     core::markdown::NotesFrontMatter,
     system::{self},
     write_coloured, write_colouredln,
@@ -17,17 +19,6 @@ use crate::{
 /// List all paths as an absolute path.
 pub fn default(mut entries: BinaryHeap<ListEntry>) -> Result<(), Box<dyn Error>> {
     while let Some(entry) = entries.pop() {
-        let NotesFrontMatter {
-            title: _,
-            date: _,
-            tags: _,
-            notes_metadata,
-        } = &entry.frontmatter;
-
-        if notes_metadata.hidden {
-            continue;
-        }
-
         let mut stdout = std::io::stdout().lock();
         if stdout.is_terminal() && std::env::var("NOTES_HIDE_ROOT").is_ok_and(|s| s == "true") {
             let mut shortened_path = String::new();
@@ -71,10 +62,6 @@ pub fn full(mut entries: BinaryHeap<ListEntry>) -> Result<(), Box<dyn Error>> {
             notes_metadata,
         } = &entry.frontmatter;
 
-        if notes_metadata.hidden {
-            continue;
-        }
-
         if std::io::stdout().is_terminal()
             && std::env::var("NOTES_HIDE_ROOT").is_ok_and(|s| s == "true")
         {
@@ -142,7 +129,7 @@ pub fn full(mut entries: BinaryHeap<ListEntry>) -> Result<(), Box<dyn Error>> {
             gap = formatted_dt.chars().count() + DATEPAD
         )?;
 
-        let lines = entry.contents.lines();
+        let lines = entry.body()?.lines();
         for l in lines {
             writeln!(out, "{}", l?)?;
         }
@@ -167,10 +154,6 @@ pub fn short(
             notes_metadata,
         } = &entry.frontmatter;
 
-        if notes_metadata.hidden {
-            continue;
-        }
-
         let mut iter = entry.path.iter();
         let file = iter.next_back();
 
@@ -208,3 +191,119 @@ pub fn short(
     }
     Ok(())
 }
+
+/// Record of a note for machine-readable output
+#[derive(Serialize)]
+struct NoteRecord<'a> {
+    path: &'a str,
+    stack: &'a str,
+    title: &'a str,
+    date: &'a str,
+    category: Option<&'a str>,
+    subcategories: &'a [String],
+    tags: &'a [String],
+    hidden: bool,
+}
+
+/// List all notes as machine-readable records
+pub fn records(
+    entries: BinaryHeap<ListEntry>,
+    stack: &str,
+    format: Format,
+) -> Result<(), Box<dyn Error>> {
+    let entries = entries.into_sorted_vec();
+    let records = Vec::from_iter(entries.iter().rev().map(|entry| {
+        let NotesFrontMatter {
+            title,
+            date,
+            tags,
+            notes_metadata,
+        } = &entry.frontmatter;
+        NoteRecord {
+            path: entry
+                .path
+                .to_str()
+                .expect("An invalid UTF-8 sequence provided as a path"),
+            stack,
+            title,
+            date,
+            category: notes_metadata.category.as_deref(),
+            subcategories: notes_metadata.subcategories.as_deref().unwrap_or_default(),
+            tags: tags.as_deref().unwrap_or_default(),
+            hidden: notes_metadata.hidden,
+        }
+    }));
+    formatted(
+        &records,
+        &[
+            "path",
+            "stack",
+            "title",
+            "date",
+            "category",
+            "subcategories",
+            "tags",
+            "hidden",
+        ],
+        format,
+    )
+}
+
+/// Writes records in the requested format. Delimited formats use the provided columns,
+/// joining lists with commas.
+pub fn formatted<T: Serialize>(
+    records: &[T],
+    columns: &[&str],
+    format: Format,
+) -> Result<(), Box<dyn Error>> {
+    let mut stdout = std::io::stdout().lock();
+    match format {
+        Format::Json => {
+            // surfaced as io errors so that broken pipes are handled
+            serde_json::to_writer_pretty(&mut stdout, records).map_err(std::io::Error::from)?;
+            writeln!(stdout)?;
+        }
+        Format::Ndjson => {
+            for record in records {
+                serde_json::to_writer(&mut stdout, record).map_err(std::io::Error::from)?;
+                writeln!(stdout)?;
+            }
+        }
+        Format::Csv | Format::Tsv => {
+            let (separator, escape): (&str, fn(String) -> String) = match format {
+                Format::Csv => (",", |field| {
+                    if field.contains([',', '"', '\n', '\r']) {
+                        format!("\"{}\"", field.replace('"', "\"\""))
+                    } else {
+                        field
+                    }
+                }),
+                _ => ("\t", |field| {
+                    field
+                        .replace('\\', "\\\\")
+                        .replace('\t', "\\t")
+                        .replace('\n', "\\n")
+                        .replace('\r', "\\r")
+                }),
+            };
+            writeln!(stdout, "{}", columns.join(separator))?;
+            for record in records {
+                let value = serde_json::to_value(record)?;
+                let row =
+                    Vec::from_iter(columns.iter().map(|column| escape(field(&value[column]))));
+                writeln!(stdout, "{}", row.join(separator))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Flattens a record value into a single delimited field
+fn field(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::Null => String::new(),
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Array(values) => Vec::from_iter(values.iter().map(field)).join(","),
+        value => value.to_string(),
+    }
+}
@@ -0,0 +1,51 @@
+use std::{error::Error, path::PathBuf};
+
+use git2::Repository;
+
+use crate::{core::merge, system::Configuration};
+
+use super::{Command, Commands};
+
+/// Representation of the merge driver git runs for notes, which merges the front matter of
+/// a note field by field and writes the result over the current version
+pub struct MergeDriverCommand {
+    ancestor: PathBuf,
+    current: PathBuf,
+    other: PathBuf,
+}
+
+impl Command<'_> for MergeDriverCommand {
+    fn new(args: Commands, _conf: &Configuration) -> Result<Self, Box<dyn Error>> {
+        let Commands::MergeDriver {
+            ancestor,
+            current,
+            other,
+        } = args
+        else {
+            unreachable!("Non-merge-driver command passed to merge driver handler.");
+        };
+
+        Ok(Self {
+            ancestor,
+            current,
+            other,
+        })
+    }
+
+    fn execute(self) -> Result<(), Box<dyn Error>> {
+        // git runs merge drivers from the root of the working tree
+        let repo = Repository::open_from_env()?;
+        let (merged, conflicted) = merge::note(
+            &repo,
+            &std::fs::read_to_string(&self.ancestor)?,
+            &std::fs::read_to_string(&self.current)?,
+            &std::fs::read_to_string(&self.other)?,
+        )?;
+        std::fs::write(&self.current, merged)?;
+        if conflicted {
+            // a non-zero exit tells git the note still conflicts
+            return Err("the body of the note has conflicting changes".into());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,187 @@
+use std::{
+    error::Error,
+    fs::OpenOptions,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use crate::core::{
+    frontmatter,
+    markdown::{self, Metadata},
+    resolve,
+};
+
+use super::{
+    Command, Commands, Configuration,
+    create::{validate_name, validate_path},
+    stack::invariants::{
+        disallow_files_with_extensions, require_existing_stack, require_plain_name,
+    },
+};
+
+/// Representation of a move or copy command that relocates a note, possibly into another note
+/// stack, and keeps its front matter in sync
+pub struct MoveCommand {
+    root: PathBuf,
+    // root of the note stack the note is moved into
+    target: PathBuf,
+    stack: Option<String>,
+    source: PathBuf,
+    destination: PathBuf,
+    name: String,
+    category: Option<String>,
+    copy: bool,
+}
+
+impl Command<'_> for MoveCommand {
+    fn new(args: Commands, conf: &Configuration) -> Result<Self, Box<dyn Error>> {
+        let (note, destination, stack, copy) = match args {
+            Commands::Move {
+                note,
+                destination,
+                to_stack,
+            } => (note, destination, to_stack, false),
+            Commands::Copy {
+                note,
+                destination,
+                to_stack,
+            } => (note, destination, to_stack, true),
+            _ => unreachable!("Non-move command passed to move handler."),
+        };
+
+        let root = PathBuf::from(&conf.settings.path);
+        let source = resolve::note(&root, &note)?;
+        let target = match &stack {
+            Some(stack) => disallow_files_with_extensions(
+                root.parent()
+                    .ok_or("unable to get parent path for move command")?
+                    .join(stack),
+            )
+            .and_then(require_plain_name)
+            .and_then(require_existing_stack)?,
+            None => root.clone(),
+        };
+
+        let (category, name) = match destination {
+            Some(destination) => {
+                // a trailing separator moves the note into a category keeping its name
+                let destination = destination.trim_start_matches('/');
+                let (category, name) = match destination.rsplit_once('/') {
+                    Some((category, "")) => (category, source_name(&source)?),
+                    Some((category, name)) => (category, name),
+                    None => ("", destination),
+                };
+                let name = validate_name(name.strip_suffix(".md").unwrap_or(name).to_owned())?;
+                if name.is_empty() {
+                    return Err("a name must be provided to move a note to".into());
+                }
+                let category = if category.is_empty() {
+                    None
+                } else {
+                    Some(validate_path(category)?.to_owned())
+                };
+                (category, name)
+            }
+            // the note keeps its category path within the other note stack
+            None => {
+                let relative = resolve::relative(&root, &source);
+                let category = relative
+                    .rsplit_once('/')
+                    .map(|(category, _)| category.to_owned());
+                (category, source_name(&source)?.to_owned())
+            }
+        };
+
+        let mut destination = category
+            .as_ref()
+            .map_or_else(|| target.clone(), |category| target.join(category));
+        destination.push(format!("{name}.md"));
+
+        Ok(Self {
+            root,
+            stack: stack.filter(|_| target != Path::new(&conf.settings.path)),
+            target,
+            source,
+            destination,
+            name,
+            category,
+            copy,
+        })
+    }
+
+    fn execute(self) -> Result<(), Box<dyn Error>> {
+        let verb = if self.copy { "copy" } else { "move" };
+        if self.destination == self.source {
+            return Err(format!(
+                "'{}' is already at the destination",
+                resolve::relative(&self.root, &self.source)
+            )
+            .into());
+        }
+        if self.destination.try_exists()? {
+            return Err(format!(
+                "cannot {verb} note as '{}' already exists{}",
+                resolve::relative(&self.target, &self.destination),
+                self.stack
+                    .as_ref()
+                    .map_or_else(String::new, |stack| format!(" in note stack '{stack}'"))
+            )
+            .into());
+        }
+
+        let contents = std::fs::read_to_string(&self.source)?;
+        // a note keeping its category path and name keeps its front matter untouched
+        let contents = if resolve::relative(&self.root, &self.source)
+            == resolve::relative(&self.target, &self.destination)
+        {
+            contents
+        } else {
+            let renamed = source_name(&self.source)? != self.name;
+            frontmatter::rewrite(&contents, |fm| {
+                fm.notes_metadata = Metadata {
+                    hidden: fm.notes_metadata.hidden,
+                    ..Metadata::new(self.category.as_deref())
+                };
+                if renamed {
+                    fm.title = markdown::title(&self.name);
+                }
+            })
+            .map_err(|err| format!("{}: {err}", self.source.display()))?
+        };
+
+        // Create the category if it does not exist
+        let parent = self
+            .destination
+            .parent()
+            .expect("a invalid path provided at move");
+        if !std::fs::exists(parent)? {
+            std::fs::create_dir_all(parent)?
+        };
+        OpenOptions::new()
+            .create_new(true)
+            .write(true)
+            .open(&self.destination)?
+            .write_all(contents.as_bytes())?;
+        if !self.copy {
+            std::fs::remove_file(&self.source)?;
+        }
+
+        println!(
+            "{} '{}' to '{}'{}",
+            if self.copy { "copied" } else { "moved" },
+            resolve::relative(&self.root, &self.source),
+            resolve::relative(&self.target, &self.destination),
+            self.stack
+                .as_ref()
+                .map_or_else(String::new, |stack| format!(" in note stack '{stack}'"))
+        );
+        Ok(())
+    }
+}
+
+fn source_name(source: &Path) -> Result<&str, Box<dyn Error>> {
+    source
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .ok_or_else(|| "unable to get the file name of the note to move".into())
+}
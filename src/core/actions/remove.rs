@@ -0,0 +1,39 @@
+use std::{error::Error, path::PathBuf};
+
+use crate::core::resolve;
+
+use super::{Command, Commands, Configuration, trash};
+
+/// Representation of a remove command that moves notes into the trash
+pub struct RemoveCommand {
+    root: PathBuf,
+    paths: Vec<PathBuf>,
+}
+
+impl Command<'_> for RemoveCommand {
+    fn new(args: Commands, conf: &Configuration) -> Result<Self, Box<dyn Error>> {
+        let Commands::Remove { notes } = args else {
+            unreachable!("Non-remove command passed to remove handler.");
+        };
+
+        let root = PathBuf::from(&conf.settings.path);
+        Ok(Self {
+            paths: notes
+                .iter()
+                .map(|note| resolve::note(&root, note))
+                .collect::<Result<_, _>>()?,
+            root,
+        })
+    }
+
+    fn execute(self) -> Result<(), Box<dyn Error>> {
+        for path in self.paths {
+            let id = trash::discard(&self.root, &path)?;
+            println!(
+                "moved '{}' to the trash as '{id}'",
+                resolve::relative(&self.root, &path)
+            );
+        }
+        Ok(())
+    }
+}
@@ -1,71 +1,154 @@
-use std::path::Path;
+use std::{error::Error, path::Path};
 
-use chrono::Local;
+use git2::Repository;
 
-use crate::system::Configuration;
+use crate::{
+    core::{
+        frontmatter::{self, Document},
+        git::{self, Change},
+    },
+    system::Configuration,
+};
 
 use super::{Command, Commands};
 
+/// Representation of a command that commits the note stack and optionally pushes it
 pub struct SaveCommand<'a> {
     path: &'a Path,
-    #[allow(unused)]
     remote: bool,
+    message: Option<String>,
+    each: bool,
 }
 
 impl<'a> Command<'a> for SaveCommand<'a> {
-    fn new(args: Commands, conf: &'a Configuration) -> Result<Self, Box<dyn std::error::Error>>
+    fn new(args: Commands, conf: &'a Configuration) -> Result<Self, Box<dyn Error>>
     where
         Self: Sized,
     {
-        let Commands::Save { remote } = args else {
+        let Commands::Save {
+            remote,
+            message,
+            each,
+        } = args
+        else {
             unreachable!("Non-save command passed to save handler.");
         };
 
         Ok(Self {
             path: Path::new(conf.settings.path.as_str()),
             remote,
+            message,
+            each,
         })
     }
 
-    // FIXME: maybe don't spawn 5 processes just to use git at some point
-    fn execute(self) -> Result<(), Box<dyn std::error::Error>> {
-        // check to see if the repository has been initialised
-        if !std::path::Path::new(self.path).join(".git").exists() {
-            std::process::Command::new("git")
-                .args(["-C", self.path.to_str().unwrap(), "init"])
-                .status()?;
-            std::fs::write(
-                std::path::Path::new(self.path).join(".gitignore"),
-                ".DS_Store",
-            )?;
+    fn execute(self) -> Result<(), Box<dyn Error>> {
+        let repo = git::open(self.path)?;
+        let changes = git::stage(&repo)?;
+        if changes.is_empty() {
+            println!("nothing to save");
+        } else if self.each {
+            let mut commits = Vec::new();
+            for change in changes {
+                let message = summary(&repo, std::slice::from_ref(&change))?;
+                commits.push((change, message));
+            }
+            let oids = git::commit_each(&repo, &commits)?;
+            for ((change, _), oid) in commits.iter().zip(oids) {
+                println!("{:.7} {change}", oid.to_string());
+            }
+            println!("saved {} change(s) in separate commits", commits.len());
+        } else {
+            for change in &changes {
+                println!("{change}");
+            }
+            let message = match self.message {
+                Some(message) => message,
+                None => summary(&repo, &changes)?,
+            };
+            let oid = git::commit(&repo, &message)?;
+            println!(
+                "saved {} change(s) in {:.7}",
+                changes.len(),
+                oid.to_string()
+            );
         }
 
-        std::process::Command::new("git")
-            .args(["-C", self.path.to_str().unwrap(), "add", "."])
-            .status()?;
+        if self.remote {
+            let remote = git::push(&repo)?;
+            println!("pushed to '{remote}'");
+        }
 
-        std::process::Command::new("git")
-            .args([
-                "-C",
-                self.path.to_str().unwrap(),
-                "commit",
-                "-m",
-                &format!("update notes: {}", Local::now().to_rfc3339()),
-            ])
-            .status()?;
+        Ok(())
+    }
+}
 
-        if !std::process::Command::new("git")
-            .args(["-C", self.path.to_str().unwrap(), "remote"])
-            .output()?
-            .stdout
-            .to_vec()
-            .is_empty()
-        {
-            std::process::Command::new("git")
-                .args(["-C", self.path.to_str().unwrap(), "push"])
-                .status()?;
+/// Commit message summarising staged changes. A single change is described in the subject,
+/// otherwise changes are counted in the subject and described in the body.
+pub(super) fn summary(repo: &Repository, changes: &[Change]) -> Result<String, Box<dyn Error>> {
+    let mut lines = Vec::new();
+    let mut counts = Vec::<(&str, &str, usize)>::new();
+    for change in changes {
+        let (verb, path, line) = match change {
+            Change::Added(path) => ("add", path, describe(path, git::staged_file(repo, path)?)),
+            Change::Modified(path) => (
+                "update",
+                path,
+                describe(path, git::staged_file(repo, path)?),
+            ),
+            Change::Deleted(path) => (
+                "remove",
+                path,
+                describe(path, git::committed_file(repo, path)?),
+            ),
+            Change::Moved(from, to) => (
+                "move",
+                to,
+                format!(
+                    "{} to {}",
+                    describe(from, git::committed_file(repo, from)?),
+                    to.display()
+                ),
+            ),
         };
+        let kind = if is_note(path) { "note" } else { "file" };
+        match counts.iter_mut().find(|(v, k, _)| *v == verb && *k == kind) {
+            Some((_, _, count)) => *count += 1,
+            None => counts.push((verb, kind, 1)),
+        }
+        lines.push(format!("{verb} {line}"));
+    }
 
-        Ok(())
+    if let [line] = lines.as_slice() {
+        return Ok(line.clone());
     }
+    let subject = Vec::from_iter(counts.iter().map(|(verb, kind, count)| {
+        format!(
+            "{verb} {count} {kind}{}",
+            if *count == 1 { "" } else { "s" }
+        )
+    }));
+    Ok(format!("{}\n\n{}\n", subject.join(", "), lines.join("\n")))
+}
+
+/// Title and category of a note, or the path of any other file
+pub(super) fn describe(path: &Path, contents: Option<String>) -> String {
+    if is_note(path)
+        && let Some(contents) = contents
+        && let Some((yaml, _)) = frontmatter::split(&contents)
+        && let Ok(document) = Document::parse(yaml)
+    {
+        let frontmatter = document.frontmatter;
+        let mut category = Vec::from_iter(frontmatter.notes_metadata.category);
+        category.extend(frontmatter.notes_metadata.subcategories.unwrap_or_default());
+        if category.is_empty() {
+            return format!("'{}'", frontmatter.title);
+        }
+        return format!("'{}' in {}", frontmatter.title, category.join("/"));
+    }
+    path.display().to_string()
+}
+
+fn is_note(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "md")
 }
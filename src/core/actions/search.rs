@@ -0,0 +1,126 @@
+use std::{
+    collections::BinaryHeap,
+    error::Error,
+    io::{BufRead, IsTerminal, Write},
+    path::PathBuf,
+};
+
+use regex::{Regex, RegexBuilder};
+use termcolor::{Color, StandardStream, WriteColor};
+
+use crate::{system::Configuration, write_coloured};
+
+use super::{
+    Command, Commands,
+    list::{ListEntry, Visibility, root_bfs_walk},
+};
+
+/// Representation of a full-text search across the bodies of notes
+pub struct SearchCommand {
+    path: PathBuf,
+    filter: Option<String>,
+    pattern: Regex,
+    context: usize,
+}
+
+impl Command<'_> for SearchCommand {
+    fn new(args: Commands, conf: &Configuration) -> Result<Self, Box<dyn Error>> {
+        let Commands::Search {
+            regex,
+            ignore_case,
+            context,
+            category,
+            pattern,
+        } = args
+        else {
+            unreachable!("Non-search command passed to search handler.");
+        };
+
+        let pattern = if regex {
+            pattern
+        } else {
+            regex::escape(&pattern)
+        };
+
+        Ok(Self {
+            path: PathBuf::from(&conf.settings.path),
+            filter: category,
+            pattern: RegexBuilder::new(&pattern)
+                .case_insensitive(ignore_case)
+                .build()?,
+            context,
+        })
+    }
+
+    fn execute(self) -> Result<(), Box<dyn Error>> {
+        let mut heap = BinaryHeap::<ListEntry>::new();
+        root_bfs_walk(
+            &self.path,
+            self.filter.as_deref(),
+            Visibility::Visible,
+            &mut heap,
+        )?;
+        let mut entries = heap.into_vec();
+        entries.sort_by(|a, b| a.path.cmp(&b.path));
+
+        let mut out = StandardStream::stdout(termcolor::ColorChoice::Always);
+        for entry in entries {
+            let path = entry.path.to_string_lossy().to_string();
+            let lines = entry.body()?.lines().collect::<Result<Vec<_>, _>>()?;
+            let matches: Vec<usize> = lines
+                .iter()
+                .enumerate()
+                .filter(|(_, l)| self.pattern.is_match(l))
+                .map(|(i, _)| i)
+                .collect();
+
+            // last line printed for the note, used to join overlapping context
+            let mut printed: Option<usize> = None;
+            for &m in &matches {
+                let start = m.saturating_sub(self.context);
+                let end = (m + self.context).min(lines.len() - 1);
+                let start = match printed {
+                    Some(p) if p + 1 >= start => p + 1,
+                    Some(_) if self.context > 0 => {
+                        writeln!(out, "--")?;
+                        start
+                    }
+                    _ => start,
+                };
+                for (i, line) in lines.iter().enumerate().take(end + 1).skip(start) {
+                    let number = entry.offset + i + 1;
+                    let separator = if matches.binary_search(&i).is_ok() {
+                        ':'
+                    } else {
+                        '-'
+                    };
+                    write_coloured!(out, colour = Color::Green, "{path}");
+                    write!(out, "{separator}")?;
+                    write_coloured!(out, colour = Color::Yellow, "{number}");
+                    write!(out, "{separator}")?;
+                    if separator == ':' {
+                        self.highlight(&mut out, line)?;
+                    } else {
+                        writeln!(out, "{line}")?;
+                    }
+                }
+                printed = Some(end);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl SearchCommand {
+    /// Writes a matching line with each match emphasised
+    fn highlight(&self, out: &mut StandardStream, line: &str) -> Result<(), Box<dyn Error>> {
+        let mut last = 0;
+        for m in self.pattern.find_iter(line) {
+            write!(out, "{}", &line[last..m.start()])?;
+            write_coloured!(out, bold_colour = Color::Red, "{}", m.as_str());
+            last = m.end();
+        }
+        writeln!(out, "{}", &line[last..])?;
+        Ok(())
+    }
+}
@@ -1,13 +1,19 @@
+mod copy;
 mod create;
-mod invariants;
+pub(super) mod invariants;
+mod merge;
 mod remove;
+mod rename;
 
 use std::path::{Path, PathBuf};
 
 use invariants::*;
 
+pub use copy::CopyCommand;
 pub use create::CreateCommand;
+pub use merge::MergeCommand;
 pub use remove::RemoveCommand;
+pub use rename::RenameCommand;
 
 use crate::system::Configuration;
 
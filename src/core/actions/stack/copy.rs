@@ -0,0 +1,85 @@
+use std::path::{Path, PathBuf};
+
+use crate::{
+    cli::{Commands, Stack},
+    core::{actions::Command, index::CACHE_DIR},
+};
+
+use super::{
+    disallow_existing_stack, disallow_files_with_extensions, disallow_reserved_names,
+    require_existing_stack, require_plain_name,
+};
+
+// history and the index belong to the original stack
+const SKIPPED: [&str; 2] = [".git", CACHE_DIR];
+
+/// Representation of a command that copies the notes of a stack into a new stack
+pub struct CopyCommand {
+    src: PathBuf,
+    dst: PathBuf,
+}
+
+impl Command<'_> for CopyCommand {
+    fn new(
+        args: crate::cli::Commands,
+        conf: &crate::system::Configuration,
+    ) -> Result<Self, Box<dyn std::error::Error>>
+    where
+        Self: Sized,
+    {
+        let Commands::Stack {
+            stack: Some(Stack::Copy { src, dst }),
+        } = args
+        else {
+            unreachable!("Non-stack copy command passed to copy handler.");
+        };
+        let stacks = Path::new(&conf.settings.path)
+            .parent()
+            .ok_or("unable to get parent path for copy command")?;
+        Ok(Self {
+            src: disallow_files_with_extensions(stacks.join(&src))
+                .and_then(require_plain_name)
+                .and_then(require_existing_stack)?,
+            dst: disallow_files_with_extensions(stacks.join(&dst))
+                .and_then(require_plain_name)
+                .and_then(disallow_reserved_names)
+                .and_then(disallow_existing_stack)?,
+        })
+    }
+
+    fn execute(self) -> Result<(), Box<dyn std::error::Error>> {
+        let count = copy(&self.src, &self.dst)?;
+        println!(
+            "copied {count} file(s) from note stack '{}' to '{}'",
+            self.src
+                .file_name()
+                .ok_or("unable to get file name for copy command")?
+                .to_string_lossy(),
+            self.dst
+                .file_name()
+                .ok_or("unable to get file name for copy command")?
+                .to_string_lossy()
+        );
+        Ok(())
+    }
+}
+
+/// Recursively copies a directory, returning the number of files copied
+fn copy(from: &Path, to: &Path) -> Result<usize, Box<dyn std::error::Error>> {
+    std::fs::create_dir_all(to)?;
+    let mut count = 0;
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        if SKIPPED.iter().any(|skipped| name == *skipped) {
+            continue;
+        }
+        if entry.file_type()?.is_dir() {
+            count += copy(&entry.path(), &to.join(&name))?;
+        } else {
+            std::fs::copy(entry.path(), to.join(&name))?;
+            count += 1;
+        }
+    }
+    Ok(count)
+}
@@ -5,7 +5,10 @@ use crate::{
     core::actions::Command,
 };
 
-use super::{disallow_operation_on_active_note_stack, disallow_reserved_names};
+use super::{
+    disallow_existing_stack, disallow_operation_on_active_note_stack, disallow_reserved_names,
+    require_plain_name,
+};
 
 pub struct CreateCommand {
     path: PathBuf,
@@ -32,9 +35,10 @@ impl Command<'_> for CreateCommand {
                     .unwrap()
                     .join(&stack),
             )
+            .and_then(require_plain_name)
             .and_then(disallow_reserved_names)
             .and_then(disallow_operation_on_active_note_stack)
-            .and_then(check_dir_exists)?,
+            .and_then(disallow_existing_stack)?,
         })
     }
 
@@ -43,16 +47,3 @@ impl Command<'_> for CreateCommand {
         Ok(())
     }
 }
-
-fn check_dir_exists(p: PathBuf) -> Result<PathBuf, Box<dyn std::error::Error>> {
-    if p.try_exists()? {
-        return Err(format!(
-            "note stack '{}' already exists",
-            p.file_name()
-                .ok_or("check_dir_exists failed for stack command")?
-                .to_string_lossy()
-        )
-        .into());
-    }
-    Ok(p)
-}
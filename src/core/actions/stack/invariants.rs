@@ -1,4 +1,6 @@
-use std::path::PathBuf;
+use std::path::{Component, Path, PathBuf};
+
+const STACKS_DIR: &str = "stacks";
 
 pub fn disallow_operation_on_active_note_stack(
     p: PathBuf,
@@ -53,3 +55,49 @@ pub fn disallow_files_with_extensions(p: PathBuf) -> Result<PathBuf, Box<dyn std
         Ok(p)
     }
 }
+
+pub fn require_existing_stack(p: PathBuf) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    if !p.try_exists()? {
+        return Err(format!(
+            "note stack '{}' does not exist",
+            p.file_name()
+                .ok_or("unable to get file name for stack command")?
+                .to_string_lossy()
+        )
+        .into());
+    }
+    Ok(p)
+}
+
+pub fn disallow_existing_stack(p: PathBuf) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    if p.try_exists()? {
+        return Err(format!(
+            "note stack '{}' already exists",
+            p.file_name()
+                .ok_or("unable to get file name for stack command")?
+                .to_string_lossy()
+        )
+        .into());
+    }
+    Ok(p)
+}
+
+pub fn require_plain_name(p: PathBuf) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    // a single name keeps the stack directly within the stacks directory
+    let within_stacks = !p
+        .components()
+        .any(|c| matches!(c, Component::CurDir | Component::ParentDir))
+        && matches!(p.components().next_back(), Some(Component::Normal(_)))
+        && p.parent()
+            .and_then(Path::file_name)
+            .is_some_and(|parent| parent == STACKS_DIR);
+    if within_stacks {
+        Ok(p)
+    } else {
+        Err(format!(
+            "'{}' is not within the stacks directory, a note stack name must not contain '/' or '..'",
+            p.display()
+        )
+        .into())
+    }
+}
@@ -0,0 +1,132 @@
+use std::path::{Path, PathBuf};
+
+use crate::{
+    cli::{Commands, Stack},
+    core::{actions::Command, resolve},
+};
+
+use super::{
+    disallow_files_with_extensions, disallow_operation_on_active_note_stack,
+    disallow_reserved_names, require_existing_stack, require_plain_name,
+};
+
+/// Representation of a command that moves every note of a stack into another stack
+pub struct MergeCommand {
+    src: PathBuf,
+    dst: PathBuf,
+}
+
+impl Command<'_> for MergeCommand {
+    fn new(
+        args: crate::cli::Commands,
+        conf: &crate::system::Configuration,
+    ) -> Result<Self, Box<dyn std::error::Error>>
+    where
+        Self: Sized,
+    {
+        let Commands::Stack {
+            stack: Some(Stack::Merge { src, dst }),
+        } = args
+        else {
+            unreachable!("Non-stack merge command passed to merge handler.");
+        };
+        let stacks = Path::new(&conf.settings.path)
+            .parent()
+            .ok_or("unable to get parent path for merge command")?;
+        let (src, dst) = (
+            disallow_files_with_extensions(stacks.join(&src))
+                .and_then(require_plain_name)
+                .and_then(require_existing_stack)
+                .and_then(disallow_reserved_names)
+                .and_then(disallow_operation_on_active_note_stack)?,
+            disallow_files_with_extensions(stacks.join(&dst))
+                .and_then(require_plain_name)
+                .and_then(require_existing_stack)?,
+        );
+        if src == dst {
+            return Err("cannot merge a note stack into itself".into());
+        }
+        Ok(Self { src, dst })
+    }
+
+    fn execute(self) -> Result<(), Box<dyn std::error::Error>> {
+        let name = self
+            .src
+            .file_name()
+            .ok_or("unable to get file name for merge command")?
+            .to_string_lossy()
+            .into_owned();
+        let (mut moved, mut duplicates) = (0, 0);
+        for note in resolve::notes(&self.src)? {
+            let relative = note.strip_prefix(&self.src)?;
+            let mut target = self.dst.join(relative);
+            if target.try_exists()? {
+                if std::fs::read(&note)? == std::fs::read(&target)? {
+                    // already in the destination stack
+                    std::fs::remove_file(&note)?;
+                    duplicates += 1;
+                    continue;
+                }
+                target = vacant(&target, &name)?;
+                println!(
+                    "renamed '{}' to '{}'",
+                    relative.display(),
+                    resolve::relative(&self.dst, &target)
+                );
+            }
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::rename(&note, &target)?;
+            moved += 1;
+        }
+        prune(&self.src)?;
+
+        println!(
+            "moved {moved} note(s) from note stack '{name}' into '{}'",
+            self.dst
+                .file_name()
+                .ok_or("unable to get file name for merge command")?
+                .to_string_lossy()
+        );
+        if duplicates > 0 {
+            println!("dropped {duplicates} note(s) identical to one already in the stack");
+        }
+        println!("'{name}' can now be removed with `notes stack remove {name}`");
+        Ok(())
+    }
+}
+
+/// First free path for a note whose path is taken, suffixed with the name of the stack it
+/// came from and then a counter
+fn vacant(target: &Path, stack: &str) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let stem = target
+        .file_stem()
+        .ok_or("unable to get file stem for merge command")?
+        .to_string_lossy();
+    let mut candidate = target.with_file_name(format!("{stem}-{stack}.md"));
+    let mut counter = 2;
+    while candidate.try_exists()? {
+        candidate = target.with_file_name(format!("{stem}-{stack}-{counter}.md"));
+        counter += 1;
+    }
+    Ok(candidate)
+}
+
+/// Removes directories left empty by moving their notes out, skipping hidden directories
+fn prune(dir: &Path) -> Result<bool, Box<dyn std::error::Error>> {
+    let mut empty = true;
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        if entry.file_type()?.is_dir()
+            && !entry.file_name().to_string_lossy().starts_with('.')
+            && prune(&path)?
+        {
+            std::fs::remove_dir(&path)?;
+        } else {
+            empty = false;
+        }
+    }
+    Ok(empty)
+}
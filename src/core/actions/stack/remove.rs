@@ -7,7 +7,7 @@ use crate::{
 
 use super::{
     disallow_files_with_extensions, disallow_operation_on_active_note_stack,
-    disallow_reserved_names,
+    disallow_reserved_names, require_existing_stack, require_plain_name,
 };
 
 pub struct RemoveCommand {
@@ -35,7 +35,8 @@ impl Command<'_> for RemoveCommand {
                     .ok_or("unable to get parent path for remove command")?
                     .join(&stack),
             )
-            .and_then(check_dir_exists)
+            .and_then(require_plain_name)
+            .and_then(require_existing_stack)
             .and_then(disallow_reserved_names)
             .and_then(disallow_operation_on_active_note_stack)?,
         })
@@ -54,16 +55,3 @@ impl Command<'_> for RemoveCommand {
         Ok(())
     }
 }
-
-pub fn check_dir_exists(p: PathBuf) -> Result<PathBuf, Box<dyn std::error::Error>> {
-    if !p.try_exists()? {
-        return Err(format!(
-            "note stack '{}' does not exist",
-            p.file_stem()
-                .ok_or("check_dir_exist failed for stack command")?
-                .to_string_lossy()
-        )
-        .into());
-    }
-    Ok(p)
-}
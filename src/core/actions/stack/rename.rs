@@ -0,0 +1,78 @@
+use std::path::{Path, PathBuf};
+
+use crate::{
+    cli::{Commands, Stack},
+    core::actions::Command,
+};
+
+use super::{
+    disallow_existing_stack, disallow_files_with_extensions, disallow_reserved_names,
+    require_existing_stack, require_plain_name,
+};
+
+/// Representation of a command that renames a note stack
+pub struct RenameCommand {
+    old: PathBuf,
+    new: PathBuf,
+    active: bool,
+}
+
+impl Command<'_> for RenameCommand {
+    fn new(
+        args: crate::cli::Commands,
+        conf: &crate::system::Configuration,
+    ) -> Result<Self, Box<dyn std::error::Error>>
+    where
+        Self: Sized,
+    {
+        let Commands::Stack {
+            stack: Some(Stack::Rename { old, new }),
+        } = args
+        else {
+            unreachable!("Non-stack rename command passed to rename handler.");
+        };
+        let stacks = Path::new(&conf.settings.path)
+            .parent()
+            .ok_or("unable to get parent path for rename command")?;
+        let old = disallow_files_with_extensions(stacks.join(&old))
+            .and_then(require_plain_name)
+            .and_then(require_existing_stack)
+            .and_then(disallow_reserved_names)?;
+        Ok(Self {
+            active: old == Path::new(&conf.settings.path),
+            old,
+            new: disallow_files_with_extensions(stacks.join(&new))
+                .and_then(require_plain_name)
+                .and_then(disallow_reserved_names)
+                .and_then(disallow_existing_stack)?,
+        })
+    }
+
+    fn execute(self) -> Result<(), Box<dyn std::error::Error>> {
+        std::fs::rename(&self.old, &self.new)?;
+        let name = self
+            .new
+            .file_name()
+            .ok_or("unable to get file name for rename command")?
+            .to_string_lossy();
+        if self.active {
+            // keep the renamed stack active
+            let notes_base = self
+                .new
+                .parent()
+                .ok_or("Failed to fetch parent in stack rename")?
+                .parent()
+                .ok_or("Failed to fetch parent in stack rename")?
+                .join(".notes");
+            std::fs::write(notes_base, format!("stack: {name}"))?;
+        }
+        println!(
+            "renamed note stack '{}' to '{name}'",
+            self.old
+                .file_name()
+                .ok_or("unable to get file name for rename command")?
+                .to_string_lossy()
+        );
+        Ok(())
+    }
+}
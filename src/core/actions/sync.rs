@@ -0,0 +1,151 @@
+use std::{
+    error::Error,
+    path::{Path, PathBuf},
+};
+
+use git2::Repository;
+
+use crate::{
+    core::git::{self, Integration, Strategy},
+    system::Configuration,
+};
+
+use super::{Command, Commands, save};
+
+const CONFLICT_MARKER: &str = "<<<<<<<";
+
+/// Representation of a command that saves the note stack, combines it with the remote
+/// repository and pushes the result
+pub struct SyncCommand {
+    root: PathBuf,
+    strategy: Strategy,
+    abort: bool,
+}
+
+impl Command<'_> for SyncCommand {
+    fn new(args: Commands, conf: &Configuration) -> Result<Self, Box<dyn Error>> {
+        let Commands::Sync { merge, abort } = args else {
+            unreachable!("Non-sync command passed to sync handler.");
+        };
+
+        Ok(Self {
+            root: PathBuf::from(&conf.settings.path),
+            strategy: if merge {
+                Strategy::Merge
+            } else {
+                Strategy::Rebase
+            },
+            abort,
+        })
+    }
+
+    fn execute(self) -> Result<(), Box<dyn Error>> {
+        let repo = git::open(&self.root)?;
+        if self.abort {
+            if !git::merging(&repo) {
+                return Err("there is no stopped sync to abort".into());
+            }
+            git::abort(&repo)?;
+            println!("aborted the sync, the stack is as it was before merging");
+            return Ok(());
+        }
+
+        if git::merging(&repo) {
+            self.conclude(&repo)?;
+        } else {
+            let changes = git::stage(&repo)?;
+            if !changes.is_empty() {
+                let oid = git::commit(&repo, &save::summary(&repo, &changes)?)?;
+                println!(
+                    "saved {} change(s) in {:.7}",
+                    changes.len(),
+                    oid.to_string()
+                );
+            }
+
+            let (remote, upstream) = git::fetch(&repo)?;
+            if let Some(upstream) = upstream {
+                let message = format!("merge notes from '{remote}'");
+                match git::integrate(&repo, upstream, self.strategy, &message)? {
+                    Integration::UpToDate => println!("up to date with '{remote}'"),
+                    Integration::FastForward => println!("updated from '{remote}'"),
+                    Integration::Rebased(count) => {
+                        println!("rebased {count} commit(s) onto '{remote}'")
+                    }
+                    Integration::Merged => println!("merged notes from '{remote}'"),
+                    Integration::Conflicts(paths) => {
+                        self.report(&repo, &paths)?;
+                        return Err(match self.strategy {
+                            Strategy::Rebase => format!(
+                                "{} note(s) conflict with '{remote}', nothing was changed; \
+                                 use `notes sync --merge` to resolve them by hand",
+                                paths.len()
+                            ),
+                            Strategy::Merge => format!(
+                                "{} note(s) conflict with '{remote}'; resolve the conflict \
+                                 markers and run `notes sync` again, or `notes sync --abort`",
+                                paths.len()
+                            ),
+                        }
+                        .into());
+                    }
+                }
+            }
+        }
+
+        let remote = git::push(&repo)?;
+        println!("pushed to '{remote}'");
+        Ok(())
+    }
+}
+
+impl SyncCommand {
+    /// Commits a merge that stopped on conflicts once every conflict marker is resolved
+    fn conclude(&self, repo: &Repository) -> Result<(), Box<dyn Error>> {
+        let mut unresolved = Vec::new();
+        for path in git::conflicts(repo)? {
+            if has_markers(&self.root.join(&path))? {
+                unresolved.push(path);
+            }
+        }
+        if !unresolved.is_empty() {
+            self.report(repo, &unresolved)?;
+            return Err(format!(
+                "{} note(s) still have conflict markers; resolve them and run `notes sync` \
+                 again, or `notes sync --abort`",
+                unresolved.len()
+            )
+            .into());
+        }
+
+        git::stage(repo)?;
+        let oid = git::commit_merge(repo, "merge notes after resolving conflicts")?;
+        println!("merged resolved notes in {:.7}", oid.to_string());
+        Ok(())
+    }
+
+    /// Lists conflicting notes by path along with their title
+    fn report(&self, repo: &Repository, paths: &[PathBuf]) -> Result<(), Box<dyn Error>> {
+        for path in paths {
+            let description = save::describe(path, git::committed_file(repo, path)?);
+            if description == path.display().to_string() {
+                eprintln!("conflict: {}", path.display());
+            } else {
+                eprintln!("conflict: {} ({description})", path.display());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Whether a file still contains the start of a conflict
+fn has_markers(path: &Path) -> Result<bool, Box<dyn Error>> {
+    match std::fs::read_to_string(path) {
+        Ok(contents) => Ok(contents
+            .lines()
+            .any(|line| line.starts_with(CONFLICT_MARKER))),
+        // removed while resolving the conflict
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(false),
+        Err(err) => Err(err.into()),
+    }
+}
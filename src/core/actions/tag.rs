@@ -0,0 +1,118 @@
+use std::{collections::BinaryHeap, error::Error, path::PathBuf};
+
+use crate::{
+    cli::{Commands, Tag, TagEdit},
+    core::{frontmatter, resolve},
+    system::Configuration,
+};
+
+use super::{
+    Command,
+    list::{ListEntry, Visibility, has_tags, root_bfs_walk},
+};
+
+enum TagOption {
+    Add,
+    Remove,
+    Set,
+}
+
+/// Representation of a command that edits the tags of existing notes
+pub struct TagCommand {
+    root: PathBuf,
+    paths: Vec<PathBuf>,
+    tags: Vec<String>,
+    action: TagOption,
+}
+
+impl Command<'_> for TagCommand {
+    fn new(args: Commands, conf: &Configuration) -> Result<Self, Box<dyn Error>> {
+        let Commands::Tag { tag } = args else {
+            unreachable!("Non-tag command passed to tag handler.");
+        };
+        let (action, edit) = match tag {
+            Tag::Add(edit) => (TagOption::Add, edit),
+            Tag::Remove(edit) => (TagOption::Remove, edit),
+            Tag::Set(edit) => (TagOption::Set, edit),
+        };
+        let TagEdit {
+            batch,
+            category,
+            with_tags,
+            tag_match,
+            mut args,
+        } = edit;
+
+        let root = PathBuf::from(&conf.settings.path);
+        let paths = if batch {
+            if matches!(action, TagOption::Set)
+                && args.is_empty()
+                && category.is_none()
+                && with_tags.is_empty()
+            {
+                return Err(
+                    "setting no tags in batch mode would clear the tags of every note; \
+                            filter the notes with --category or --with-tag"
+                        .into(),
+                );
+            }
+            let mut entries = BinaryHeap::<ListEntry>::new();
+            root_bfs_walk(
+                &root,
+                category.as_deref(),
+                Visibility::Visible,
+                &mut entries,
+            )?;
+            entries
+                .into_iter()
+                .filter(|e| with_tags.is_empty() || has_tags(&e.frontmatter, &with_tags, tag_match))
+                .map(|e| e.path)
+                .collect()
+        } else {
+            if args.is_empty() {
+                return Err("a note must be provided when not in batch mode".into());
+            }
+            vec![resolve::note(&root, &args.remove(0))?]
+        };
+
+        if args.is_empty() && !matches!(action, TagOption::Set) {
+            return Err("at least one tag must be provided".into());
+        }
+
+        Ok(Self {
+            root,
+            paths,
+            tags: args,
+            action,
+        })
+    }
+
+    fn execute(mut self) -> Result<(), Box<dyn Error>> {
+        self.paths.sort();
+        for path in &self.paths {
+            let mut result = Vec::new();
+            frontmatter::update(path, |fm| {
+                let mut tags = fm.tags.take().unwrap_or_default();
+                match self.action {
+                    TagOption::Add => {
+                        for tag in &self.tags {
+                            if !tags.contains(tag) {
+                                tags.push(tag.clone());
+                            }
+                        }
+                    }
+                    TagOption::Remove => tags.retain(|t| !self.tags.contains(t)),
+                    TagOption::Set => tags = self.tags.clone(),
+                }
+                result.clone_from(&tags);
+                fm.tags = if tags.is_empty() { None } else { Some(tags) };
+            })?;
+            println!(
+                "{}: {}",
+                resolve::relative(&self.root, path),
+                result.join(",")
+            );
+        }
+        Ok(())
+    }
+}
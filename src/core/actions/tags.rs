@@ -0,0 +1,62 @@
+use std::{
+    collections::{BTreeMap, BinaryHeap},
+    error::Error,
+    io::{IsTerminal, Write},
+    path::PathBuf,
+};
+
+use termcolor::{Color, StandardStream, WriteColor};
+
+use crate::{system::Configuration, write_coloured};
+
+use super::{
+    Command, Commands,
+    list::{ListEntry, Visibility, root_bfs_walk},
+};
+
+/// Representation of the tag index of a note stack
+pub struct TagsCommand {
+    path: PathBuf,
+    filter: Option<String>,
+}
+
+impl Command<'_> for TagsCommand {
+    fn new(args: Commands, conf: &Configuration) -> Result<Self, Box<dyn Error>> {
+        let Commands::Tags { category } = args else {
+            unreachable!("Non-tags command passed to tags handler.");
+        };
+
+        Ok(Self {
+            path: PathBuf::from(&conf.settings.path),
+            filter: category,
+        })
+    }
+
+    fn execute(self) -> Result<(), Box<dyn Error>> {
+        let mut entries = BinaryHeap::<ListEntry>::new();
+        root_bfs_walk(
+            &self.path,
+            self.filter.as_deref(),
+            Visibility::Visible,
+            &mut entries,
+        )?;
+
+        let mut counts = BTreeMap::<String, usize>::new();
+        for entry in entries {
+            for tag in entry.frontmatter.tags.unwrap_or_default() {
+                *counts.entry(tag).or_default() += 1;
+            }
+        }
+
+        let width = counts.keys().map(|t| t.chars().count()).max().unwrap_or(0);
+        let mut counts = Vec::from_iter(counts);
+        counts.sort_by(|(_, a), (_, b)| b.cmp(a));
+
+        let mut out = StandardStream::stdout(termcolor::ColorChoice::Always);
+        for (tag, count) in counts {
+            write_coloured!(out, colour = Color::Yellow, "{tag:<width$}");
+            writeln!(out, " {count}")?;
+        }
+        Ok(())
+    }
+}
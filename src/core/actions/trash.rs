@@ -0,0 +1,158 @@
+use std::{
+    error::Error,
+    io::{IsTerminal, Write},
+    path::{Path, PathBuf},
+};
+
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use termcolor::{Color, StandardStream, WriteColor};
+
+use crate::{
+    cli::{Commands, Trash},
+    core::resolve,
+    system::Configuration,
+    write_coloured,
+};
+
+use super::{Command, create::validate_path};
+
+pub const TRASH_DIR: &str = ".trash";
+
+/// Record of where a trashed note came from
+#[derive(Deserialize, Serialize)]
+struct Origin {
+    path: String,
+    deleted: String,
+}
+
+enum TrashOption {
+    List,
+    Restore(String),
+    Empty,
+}
+
+/// Representation of the operations on the trash of a note stack
+pub struct TrashCommand {
+    root: PathBuf,
+    action: TrashOption,
+}
+
+impl Command<'_> for TrashCommand {
+    fn new(args: Commands, conf: &Configuration) -> Result<Self, Box<dyn Error>> {
+        let Commands::Trash { trash } = args else {
+            unreachable!("Non-trash command passed to trash handler.");
+        };
+
+        Ok(Self {
+            root: PathBuf::from(&conf.settings.path),
+            action: match trash {
+                None | Some(Trash::List) => TrashOption::List,
+                Some(Trash::Restore { note }) => TrashOption::Restore(note),
+                Some(Trash::Empty) => TrashOption::Empty,
+            },
+        })
+    }
+
+    fn execute(self) -> Result<(), Box<dyn Error>> {
+        let trash = self.root.join(TRASH_DIR);
+        match self.action {
+            TrashOption::List => {
+                let mut out = StandardStream::stdout(termcolor::ColorChoice::Always);
+                let entries = trashed(&trash)?;
+                let width = entries.iter().map(|(id, _)| id.len()).max().unwrap_or(0);
+                for (id, origin) in entries {
+                    write_coloured!(out, colour = Color::Yellow, "{id:<width$}");
+                    write!(out, " {} ", origin.deleted)?;
+                    write_coloured!(out, colour = Color::Green, "{}", origin.path);
+                    writeln!(out)?;
+                }
+            }
+            TrashOption::Restore(note) => {
+                let (id, origin) = find(&trash, &note)?;
+                // the origin is read from the trash and may have been edited
+                let path = validate_path(&origin.path)
+                    .map_err(|err| format!("cannot restore '{id}': {err}"))?;
+                let destination = self.root.join(path);
+                if destination.try_exists()? {
+                    return Err(format!(
+                        "cannot restore '{id}' as '{}' already exists",
+                        origin.path
+                    )
+                    .into());
+                }
+                if let Some(parent) = destination.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::rename(trash.join(format!("{id}.md")), &destination)?;
+                std::fs::remove_file(trash.join(format!("{id}.toml")))?;
+                println!("restored '{}'", origin.path);
+            }
+            TrashOption::Empty => {
+                if trash.try_exists()? {
+                    let count = trashed(&trash)?.len();
+                    std::fs::remove_dir_all(&trash)?;
+                    println!("permanently removed {count} note(s)");
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Moves a note into the trash of the note stack, returning the id it can be restored by
+pub(super) fn discard(root: &Path, path: &Path) -> Result<String, Box<dyn Error>> {
+    let trash = root.join(TRASH_DIR);
+    std::fs::create_dir_all(&trash)?;
+
+    let now = Local::now();
+    let stem = path
+        .file_stem()
+        .ok_or("unable to get file name for trash")?
+        .to_string_lossy();
+    let mut id = format!("{}-{stem}", now.format("%Y%m%d%H%M%S"));
+    let mut count = 1;
+    while trash.join(format!("{id}.md")).try_exists()? {
+        count += 1;
+        id = format!("{}-{stem}-{count}", now.format("%Y%m%d%H%M%S"));
+    }
+
+    let origin = Origin {
+        path: resolve::relative(root, path),
+        deleted: now.to_rfc3339(),
+    };
+    std::fs::write(trash.join(format!("{id}.toml")), toml::to_string(&origin)?)?;
+    std::fs::rename(path, trash.join(format!("{id}.md")))?;
+    Ok(id)
+}
+
+/// Every trashed note and its origin, most recently deleted first
+fn trashed(trash: &Path) -> Result<Vec<(String, Origin)>, Box<dyn Error>> {
+    let mut entries = Vec::new();
+    if !trash.try_exists()? {
+        return Ok(entries);
+    }
+    for child in std::fs::read_dir(trash)? {
+        let path = child?.path();
+        if path.extension().is_some_and(|ext| ext == "toml") {
+            let origin = toml::from_str::<Origin>(&std::fs::read_to_string(&path)?)?;
+            let id = path.file_stem().unwrap().to_string_lossy().to_string();
+            entries.push((id, origin));
+        }
+    }
+    entries.sort_by(|(_, a), (_, b)| b.deleted.cmp(&a.deleted));
+    Ok(entries)
+}
+
+/// Finds a trashed note by id, or by the path or filename it was deleted from. The most
+/// recently deleted note is chosen when several share an origin.
+fn find(trash: &Path, note: &str) -> Result<(String, Origin), Box<dyn Error>> {
+    let note = note.strip_suffix(".md").unwrap_or(note);
+    trashed(trash)?
+        .into_iter()
+        .find(|(id, origin)| {
+            let path = origin.path.strip_suffix(".md").unwrap_or(&origin.path);
+            id == note || path == note || Path::new(path).file_name().is_some_and(|f| f == note)
+        })
+        .ok_or_else(|| format!("no trashed note matching '{note}'").into())
+}
@@ -0,0 +1,44 @@
+use std::{error::Error, path::Path};
+
+use crate::system::Configuration;
+
+pub(crate) const DEFAULT_EDITOR: &str = "vim";
+
+/// Resolves the editor used for notes, preferring the configured editor,
+/// then `NOTES_EDITOR` and finally the default editor.
+pub fn resolve(conf: &Configuration) -> &str {
+    conf.settings
+        .editor
+        .as_deref()
+        .or_else(|| environment())
+        .unwrap_or(DEFAULT_EDITOR)
+}
+
+/// Editor chosen through `NOTES_EDITOR`, if it is one of the supported editors
+pub(crate) fn environment() -> Option<&'static str> {
+    let mut vars = std::env::vars();
+    let allowed_editors: fn((String, String)) -> Option<&'static str> = |(_, v)| match v.as_str() {
+        "nvim" => Some("nvim"),
+        "glow" => Some("glow"),
+        _ => None,
+    };
+    vars.find(|(key, _)| key == "NOTES_EDITOR")
+        .and_then(allowed_editors)
+        .and_then(|s| {
+            if s == "glow" && !vars.any(|(k, _)| k == "EDITOR") {
+                None
+            } else {
+                Some(s)
+            }
+        })
+}
+
+/// Opens a note with the provided editor
+pub fn open(editor: &str, path: &Path) -> Result<(), Box<dyn Error>> {
+    let mut command = std::process::Command::new(editor);
+    if editor == "glow" {
+        command.arg(r#"--tui"#);
+    }
+    command.arg(path.as_os_str()).status()?;
+    Ok(())
+}
@@ -2,10 +2,74 @@ use std::{
     error::Error,
     fs::File,
     io::{BufRead, BufReader},
+    path::Path,
 };
 
+use serde_yaml_ng::{Mapping, Value};
+
 use super::markdown::NotesFrontMatter;
 
+/// Front matter of a note that retains every yaml key and its order, including keys that
+/// are not owned by notes, so that rewriting a note only updates the fields notes owns.
+pub struct Document {
+    yaml: Mapping,
+    pub(crate) frontmatter: NotesFrontMatter,
+}
+
+impl Document {
+    /// Parses yaml front matter, keeping hold of the original keys
+    pub fn parse(buf: &str) -> Result<Self, Box<dyn Error>> {
+        let yaml = mapping(buf)?;
+        let frontmatter = serde_yaml_ng::from_value(Value::Mapping(yaml.clone()))?;
+        Ok(Self { yaml, frontmatter })
+    }
+
+    /// Adopts yaml front matter that was not written by notes, synthesising the fields notes
+    /// owns from the original keys
+    pub fn adopt(
+        buf: &str,
+        synthesise: impl FnOnce(&Mapping) -> NotesFrontMatter,
+    ) -> Result<Self, Box<dyn Error>> {
+        let yaml = mapping(buf)?;
+        let frontmatter = synthesise(&yaml);
+        Ok(Self { yaml, frontmatter })
+    }
+
+    /// Serialises the front matter, merging the fields owned by notes into the original yaml
+    pub fn to_yaml(&self) -> Result<String, Box<dyn Error>> {
+        let mut yaml = Value::Mapping(self.yaml.clone());
+        merge(&mut yaml, serde_yaml_ng::to_value(&self.frontmatter)?);
+        Ok(serde_yaml_ng::to_string(&yaml)?)
+    }
+}
+
+/// Deserialises yaml front matter into a mapping, treating empty front matter as no keys
+pub fn mapping(buf: &str) -> Result<Mapping, Box<dyn Error>> {
+    match serde_yaml_ng::from_str::<Value>(buf)? {
+        Value::Mapping(yaml) => Ok(yaml),
+        Value::Null => Ok(Mapping::new()),
+        _ => Err("front matter is not a yaml mapping".into()),
+    }
+}
+
+/// Merges updated yaml into the original, replacing values in place so that keys keep their
+/// position and nested keys unknown to the update are retained.
+fn merge(original: &mut Value, updated: Value) {
+    match (original, updated) {
+        (Value::Mapping(original), Value::Mapping(updated)) => {
+            for (key, value) in updated {
+                match original.get_mut(&key) {
+                    Some(existing) => merge(existing, value),
+                    None => {
+                        original.insert(key, value);
+                    }
+                }
+            }
+        }
+        (original, updated) => *original = updated,
+    }
+}
+
 /// Utility function for fetching the yaml front matter of a note
 pub fn fetch(reader: &mut BufReader<File>) -> Result<String, Box<dyn Error>> {
     let lines = reader.lines();
@@ -30,3 +94,162 @@ pub fn fetch(reader: &mut BufReader<File>) -> Result<String, Box<dyn Error>> {
 pub fn generate(buf: &str) -> Result<NotesFrontMatter, Box<dyn Error>> {
     Ok(serde_yaml_ng::from_str::<NotesFrontMatter>(buf)?)
 }
+
+/// Splits the contents of a note into its yaml front matter and the body that follows
+pub fn split(contents: &str) -> Option<(&str, &str)> {
+    let rest = contents.strip_prefix("---")?;
+    let rest = rest
+        .strip_prefix("\r\n")
+        .or_else(|| rest.strip_prefix('\n'))?;
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        if line.trim() == "---" {
+            return Some((&rest[..offset], &rest[offset + line.len()..]));
+        }
+        offset += line.len();
+    }
+    None
+}
+
+/// Applies a modification to the front matter of a note, leaving the body and any yaml keys
+/// not owned by notes untouched
+pub fn rewrite(
+    contents: &str,
+    modify: impl FnOnce(&mut NotesFrontMatter),
+) -> Result<String, Box<dyn Error>> {
+    let (yaml, body) = split(contents).ok_or("note does not start with yaml front matter")?;
+    let mut document = Document::parse(yaml)?;
+    modify(&mut document.frontmatter);
+    Ok(format!("---\n{}---\n{body}", document.to_yaml()?))
+}
+
+/// Modifies the front matter of a note in place, preserving the body byte for byte
+pub fn update(
+    path: &Path,
+    modify: impl FnOnce(&mut NotesFrontMatter),
+) -> Result<(), Box<dyn Error>> {
+    let contents = rewrite(&std::fs::read_to_string(path)?, modify)
+        .map_err(|err| format!("{}: {err}", path.display()))?;
+    // written aside and renamed so that a failed write never truncates the note
+    let tmp = path.with_extension(format!("md.{}", std::process::id()));
+    std::fs::write(&tmp, contents)?;
+    std::fs::rename(tmp, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::markdown::{self, Metadata};
+
+    const NOTE: &str = "---
+aliases:
+- first
+title: Note
+cssclass: wide
+date: 2026-01-01T00:00:00+00:00
+tags:
+- a
+notes_metadata:
+  category: work
+  subcategories: []
+  hidden: true
+  colour: blue
+publish: false
+---
+# Note
+
+body kept as is
+";
+
+    fn keys(contents: &str) -> Vec<String> {
+        let (yaml, _) = split(contents).unwrap();
+        mapping(yaml)
+            .unwrap()
+            .keys()
+            .map(|key| key.as_str().unwrap().to_owned())
+            .collect()
+    }
+
+    fn value(contents: &str, key: &str) -> Value {
+        let (yaml, _) = split(contents).unwrap();
+        mapping(yaml).unwrap().get(key).cloned().unwrap_or_default()
+    }
+
+    /// Checks that everything notes does not own is unchanged
+    fn assert_retained(rewritten: &str) {
+        assert_eq!(keys(rewritten), keys(NOTE));
+        for key in ["aliases", "cssclass", "publish"] {
+            assert_eq!(value(rewritten, key), value(NOTE, key), "{key}");
+        }
+        let metadata = value(rewritten, "notes_metadata");
+        assert_eq!(
+            metadata.get("colour"),
+            value(NOTE, "notes_metadata").get("colour")
+        );
+        assert_eq!(
+            Vec::from_iter(
+                metadata
+                    .as_mapping()
+                    .unwrap()
+                    .keys()
+                    .filter_map(Value::as_str)
+            ),
+            vec!["category", "subcategories", "hidden", "colour"]
+        );
+        assert_eq!(split(rewritten).unwrap().1, split(NOTE).unwrap().1);
+    }
+
+    #[test]
+    fn tagging_keeps_unknown_keys_in_order() {
+        let path =
+            std::env::temp_dir().join(format!("notes-frontmatter-{}.md", std::process::id()));
+        std::fs::write(&path, NOTE).unwrap();
+        // as `notes tag add`
+        update(&path, |fm| {
+            let mut tags = fm.tags.take().unwrap_or_default();
+            tags.push("b".to_owned());
+            fm.tags = Some(tags);
+        })
+        .unwrap();
+        let tagged = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_retained(&tagged);
+        assert_eq!(
+            value(&tagged, "tags"),
+            serde_yaml_ng::from_str::<Value>("[a, b]").unwrap()
+        );
+    }
+
+    #[test]
+    fn moving_keeps_unknown_keys_in_order() {
+        // as `notes mv note personal/ideas/renamed`
+        let moved = rewrite(NOTE, |fm| {
+            fm.notes_metadata = Metadata {
+                hidden: fm.notes_metadata.hidden,
+                ..Metadata::new(Some("personal/ideas"))
+            };
+            fm.title = markdown::title("renamed");
+        })
+        .unwrap();
+
+        assert_retained(&moved);
+        assert_eq!(
+            value(&moved, "title"),
+            Value::from(markdown::title("renamed"))
+        );
+        let metadata = value(&moved, "notes_metadata");
+        assert_eq!(metadata.get("category"), Some(&Value::from("personal")));
+        assert_eq!(
+            metadata.get("subcategories"),
+            Some(&serde_yaml_ng::from_str::<Value>("[ideas]").unwrap())
+        );
+        assert_eq!(metadata.get("hidden"), Some(&Value::Bool(true)));
+    }
+
+    #[test]
+    fn rewriting_without_changes_is_lossless() {
+        assert_eq!(rewrite(NOTE, |_| {}).unwrap(), NOTE);
+    }
+}
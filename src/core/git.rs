@@ -0,0 +1,654 @@
+use std::{
+    error::Error,
+    fmt::Display,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+
+use git2::{
+    BranchType, Commit, ConfigLevel, Cred, CredentialType, Delta, DiffFindOptions, ErrorCode,
+    FetchOptions, IndexAddOption, IndexEntry, Oid, PushOptions, RemoteCallbacks, Repository,
+    RepositoryState, ResetType, Signature, Sort, Tree, build::CheckoutBuilder,
+};
+
+use super::{actions::TRASH_DIR, index::CACHE_DIR, merge};
+
+const GITIGNORE: &str = ".gitignore";
+const GITATTRIBUTES: &str = ".gitattributes";
+const MERGE_DRIVER: &str = "notes";
+const DEFAULT_REMOTE: &str = "origin";
+// the index cache and the trash are local to each copy of a note stack
+const UNSAVED: [&str; 2] = [CACHE_DIR, TRASH_DIR];
+const SSH_KEYS: [&str; 3] = ["id_ed25519", "id_ecdsa", "id_rsa"];
+
+/// A change to a file of a note stack, relative to the root of the note stack
+pub enum Change {
+    Added(PathBuf),
+    Modified(PathBuf),
+    Deleted(PathBuf),
+    Moved(PathBuf, PathBuf),
+}
+
+impl Display for Change {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Change::Added(path) => write!(f, "added:    {}", path.display()),
+            Change::Modified(path) => write!(f, "modified: {}", path.display()),
+            Change::Deleted(path) => write!(f, "deleted:  {}", path.display()),
+            Change::Moved(from, to) => {
+                write!(f, "moved:    {} -> {}", from.display(), to.display())
+            }
+        }
+    }
+}
+
+/// Opens the existing repository of a note stack
+pub fn repository(root: &Path) -> Result<Repository, Box<dyn Error>> {
+    Repository::open(root).map_err(|err| {
+        if err.code() == ErrorCode::NotFound {
+            "the note stack has no history yet, use `notes save` to start one".into()
+        } else {
+            format!(
+                "unable to open the repository in '{}': {err}",
+                root.display()
+            )
+            .into()
+        }
+    })
+}
+
+/// Opens the repository of a note stack, initialising it when it does not exist yet, and
+/// makes sure the index cache is never committed
+pub fn open(root: &Path) -> Result<Repository, Box<dyn Error>> {
+    let repo = match Repository::open(root) {
+        Ok(repo) => repo,
+        Err(err) if err.code() == ErrorCode::NotFound => Repository::init(root).map_err(|err| {
+            format!(
+                "unable to initialise a repository in '{}': {err}",
+                root.display()
+            )
+        })?,
+        Err(err) => {
+            return Err(format!(
+                "unable to open the repository in '{}': {err}",
+                root.display()
+            )
+            .into());
+        }
+    };
+    ignore(root)?;
+    install_merge_driver(&repo, root)?;
+    Ok(repo)
+}
+
+/// Writes the default `.gitignore` of a note stack, or adds the directories of a note stack
+/// that are never saved to an existing one
+fn ignore(root: &Path) -> Result<(), Box<dyn Error>> {
+    let path = root.join(GITIGNORE);
+    let mut contents = match std::fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == ErrorKind::NotFound => {
+            let ignored = Vec::from_iter(UNSAVED.iter().map(|dir| format!("{dir}/\n")));
+            std::fs::write(&path, format!(".DS_Store\n{}", ignored.concat()))?;
+            return Ok(());
+        }
+        Err(err) => return Err(format!("unable to read '{}': {err}", path.display()).into()),
+    };
+    let missing = Vec::from_iter(UNSAVED.iter().filter(|dir| {
+        !contents
+            .lines()
+            .any(|line| line.trim().trim_matches('/') == **dir)
+    }));
+    if missing.is_empty() {
+        return Ok(());
+    }
+    if !contents.is_empty() && !contents.ends_with('\n') {
+        contents.push('\n');
+    }
+    for dir in missing {
+        contents += &format!("{dir}/\n");
+    }
+    std::fs::write(&path, contents)?;
+    Ok(())
+}
+
+/// Configures the front matter aware merge driver for notes and assigns it to notes in
+/// `.gitattributes` so that git uses it as well
+fn install_merge_driver(repo: &Repository, root: &Path) -> Result<(), Box<dyn Error>> {
+    let exe = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.to_str().map(str::to_owned))
+        .unwrap_or_else(|| "notes".to_owned());
+    let mut config = repo.config()?.open_level(ConfigLevel::Local)?;
+    config.set_str(
+        &format!("merge.{MERGE_DRIVER}.name"),
+        "notes front matter merge",
+    )?;
+    config.set_str(
+        &format!("merge.{MERGE_DRIVER}.driver"),
+        &format!("\"{exe}\" merge-driver %O %A %B"),
+    )?;
+
+    let path = root.join(GITATTRIBUTES);
+    let attribute = format!("*.md merge={MERGE_DRIVER}");
+    let mut contents = match std::fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == ErrorKind::NotFound => String::new(),
+        Err(err) => return Err(format!("unable to read '{}': {err}", path.display()).into()),
+    };
+    if contents.lines().any(|line| line.trim() == attribute) {
+        return Ok(());
+    }
+    if !contents.is_empty() && !contents.ends_with('\n') {
+        contents.push('\n');
+    }
+    contents += &format!("{attribute}\n");
+    std::fs::write(&path, contents)?;
+    Ok(())
+}
+
+/// Stages every change within the note stack, including removed notes, and returns the
+/// changes that would be committed
+pub fn stage(repo: &Repository) -> Result<Vec<Change>, Box<dyn Error>> {
+    let mut index = repo.index()?;
+    // these may have been committed before they were ignored
+    for dir in UNSAVED {
+        index.remove_dir(Path::new(dir), 0)?;
+    }
+    index
+        .add_all(["*"], IndexAddOption::DEFAULT, None)
+        .and_then(|_| index.update_all(["*"], None))
+        .and_then(|_| index.write())
+        .map_err(|err| format!("unable to stage notes: {err}"))?;
+    staged(repo)
+}
+
+/// Changes staged in the index of the repository relative to the last commit
+pub fn staged(repo: &Repository) -> Result<Vec<Change>, Box<dyn Error>> {
+    let head = head_tree(repo)?;
+    let mut diff = repo.diff_tree_to_index(head.as_ref(), None, None)?;
+    diff.find_similar(Some(DiffFindOptions::new().renames(true)))?;
+    let mut changes = Vec::new();
+    for delta in diff.deltas() {
+        let path = |file: git2::DiffFile| PathBuf::from(file.path().unwrap_or(Path::new("")));
+        changes.push(match delta.status() {
+            Delta::Added | Delta::Copied => Change::Added(path(delta.new_file())),
+            Delta::Deleted => Change::Deleted(path(delta.old_file())),
+            Delta::Renamed => Change::Moved(path(delta.old_file()), path(delta.new_file())),
+            Delta::Modified | Delta::Typechange => Change::Modified(path(delta.new_file())),
+            _ => continue,
+        });
+    }
+    Ok(changes)
+}
+
+/// Commits each change separately in the order given, each with its own message. Every
+/// change must already be staged.
+pub fn commit_each(
+    repo: &Repository,
+    changes: &[(Change, String)],
+) -> Result<Vec<Oid>, Box<dyn Error>> {
+    let mut index = repo.index()?;
+    // the index is rewound to the last commit and each change is staged in turn
+    match head_tree(repo)? {
+        Some(tree) => index.read_tree(&tree)?,
+        None => index.clear()?,
+    }
+    let mut oids = Vec::new();
+    for (change, message) in changes {
+        match change {
+            Change::Added(path) | Change::Modified(path) => index.add_path(path)?,
+            Change::Deleted(path) => index.remove_path(path)?,
+            Change::Moved(from, to) => {
+                index.remove_path(from)?;
+                index.add_path(to)?;
+            }
+        }
+        index.write()?;
+        oids.push(commit(repo, message)?);
+    }
+    Ok(oids)
+}
+
+/// Contents of a file as staged in the index
+pub fn staged_file(repo: &Repository, path: &Path) -> Result<Option<String>, Box<dyn Error>> {
+    let Some(entry) = repo.index()?.get_path(path, 0) else {
+        return Ok(None);
+    };
+    let blob = repo.find_blob(entry.id)?;
+    Ok(Some(String::from_utf8_lossy(blob.content()).into_owned()))
+}
+
+/// Contents of a file as of the last commit
+pub fn committed_file(repo: &Repository, path: &Path) -> Result<Option<String>, Box<dyn Error>> {
+    match head_tree(repo)? {
+        Some(tree) => file_at(repo, &tree, path),
+        None => Ok(None),
+    }
+}
+
+/// Contents of a file within a tree
+pub fn file_at(
+    repo: &Repository,
+    tree: &Tree,
+    path: &Path,
+) -> Result<Option<String>, Box<dyn Error>> {
+    let Ok(entry) = tree.get_path(path) else {
+        return Ok(None);
+    };
+    let blob = repo.find_blob(entry.id())?;
+    Ok(Some(String::from_utf8_lossy(blob.content()).into_owned()))
+}
+
+/// Resolves a revision such as a commit hash, `HEAD~2` or a branch name to a commit
+pub fn revision<'a>(repo: &'a Repository, rev: &str) -> Result<Commit<'a>, Box<dyn Error>> {
+    repo.revparse_single(rev)
+        .and_then(|object| object.peel_to_commit())
+        .map_err(|err| format!("unknown revision '{rev}': {err}").into())
+}
+
+/// A commit that changed a file, along with the path of the file at that commit
+pub struct Revision<'a> {
+    pub(crate) commit: Commit<'a>,
+    pub(crate) path: PathBuf,
+    pub(crate) deleted: bool,
+}
+
+/// Commits that changed a file, newest first, following the file across moves
+pub fn history<'a>(repo: &'a Repository, path: &Path) -> Result<Vec<Revision<'a>>, Box<dyn Error>> {
+    if head_tree(repo)?.is_none() {
+        return Ok(Vec::new());
+    }
+    let mut walk = repo.revwalk()?;
+    walk.push_head()?;
+    walk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME)?;
+
+    let mut path = path.to_path_buf();
+    let mut revisions = Vec::new();
+    for oid in walk {
+        let commit = repo.find_commit(oid?)?;
+        let tree = commit.tree()?;
+        let parent = match commit.parent_count() {
+            0 => None,
+            _ => Some(commit.parent(0)?.tree()?),
+        };
+        let id = |tree: &Tree| tree.get_path(&path).ok().map(|entry| entry.id());
+        let (current, previous) = (id(&tree), parent.as_ref().and_then(id));
+        if current == previous {
+            continue;
+        }
+
+        let mut from = None;
+        if current.is_some()
+            && previous.is_none()
+            && let Some(parent) = &parent
+        {
+            // a file that appears may have been moved from elsewhere
+            let mut diff = repo.diff_tree_to_tree(Some(parent), Some(&tree), None)?;
+            diff.find_similar(Some(DiffFindOptions::new().renames(true)))?;
+            from = diff
+                .deltas()
+                .find(|delta| {
+                    delta.status() == Delta::Renamed && delta.new_file().path() == Some(&path)
+                })
+                .and_then(|delta| delta.old_file().path().map(Path::to_path_buf));
+        }
+        revisions.push(Revision {
+            commit,
+            path: path.clone(),
+            deleted: current.is_none(),
+        });
+        if let Some(from) = from {
+            path = from;
+        }
+    }
+    Ok(revisions)
+}
+
+/// Tree of the last commit, if anything has been committed yet
+fn head_tree(repo: &Repository) -> Result<Option<Tree<'_>>, Box<dyn Error>> {
+    match repo.head() {
+        Ok(head) => Ok(Some(head.peel_to_tree()?)),
+        Err(err) if matches!(err.code(), ErrorCode::UnbornBranch | ErrorCode::NotFound) => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Commits the staged changes on top of the current branch
+pub fn commit(repo: &Repository, message: &str) -> Result<Oid, Box<dyn Error>> {
+    let signature = signature(repo)?;
+    let tree = repo.find_tree(repo.index()?.write_tree()?)?;
+    let parent = match repo.head() {
+        Ok(head) => Some(head.peel_to_commit()?),
+        Err(err) if matches!(err.code(), ErrorCode::UnbornBranch | ErrorCode::NotFound) => None,
+        Err(err) => return Err(format!("unable to read the last commit: {err}").into()),
+    };
+    let parents = Vec::from_iter(parent.iter());
+    let oid = repo
+        .commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            message,
+            &tree,
+            &parents,
+        )
+        .map_err(|err| format!("unable to commit notes: {err}"))?;
+    Ok(oid)
+}
+
+/// Author and committer of new commits
+fn signature(repo: &Repository) -> Result<Signature<'static>, Box<dyn Error>> {
+    repo.signature().map_err(|err| {
+        format!("unable to determine the commit author, set user.name and user.email: {err}").into()
+    })
+}
+
+/// Pushes the current branch to its upstream remote, or `origin` when it has none, and
+/// returns the name of the remote
+pub fn push(repo: &Repository) -> Result<String, Box<dyn Error>> {
+    let head = repo
+        .head()
+        .map_err(|err| format!("nothing to push: {err}"))?;
+    let (Some(branch), Some(reference)) = (head.shorthand(), head.name()) else {
+        return Err("unable to push: HEAD is not a valid branch".into());
+    };
+    if !head.is_branch() {
+        return Err("unable to push: HEAD is detached from a branch".into());
+    }
+
+    let name = remote_name(repo, reference);
+    let mut remote = repo
+        .find_remote(&name)
+        .map_err(|err| format!("unable to push: no remote named '{name}' is configured: {err}"))?;
+
+    let mut rejected = Vec::new();
+    let mut callbacks = callbacks(repo)?;
+    callbacks.push_update_reference(|reference, status| {
+        if let Some(status) = status {
+            rejected.push(format!("{reference}: {status}"));
+        }
+        Ok(())
+    });
+    remote
+        .push(
+            &[format!("{reference}:{reference}")],
+            Some(PushOptions::new().remote_callbacks(callbacks)),
+        )
+        .map_err(|err| format!("unable to push to '{name}': {err}"))?;
+    if !rejected.is_empty() {
+        return Err(format!("'{name}' rejected the push of {}", rejected.join(", ")).into());
+    }
+
+    // tracks the remote branch so that later pushes find it
+    let mut local = repo.find_branch(branch, BranchType::Local)?;
+    if local.upstream().is_err() {
+        local.set_upstream(Some(&format!("{name}/{branch}")))?;
+    }
+    Ok(name)
+}
+
+/// Name of the remote a branch is tracking, or `origin` when it tracks none
+fn remote_name(repo: &Repository, reference: &str) -> String {
+    repo.branch_upstream_remote(reference)
+        .ok()
+        .and_then(|name| name.as_str().map(str::to_owned))
+        .unwrap_or_else(|| DEFAULT_REMOTE.to_owned())
+}
+
+/// Reference of the branch checked out, including a branch without any commits yet
+fn head_reference(repo: &Repository) -> Result<String, Box<dyn Error>> {
+    repo.find_reference("HEAD")?
+        .symbolic_target()
+        .map(str::to_owned)
+        .ok_or("HEAD is detached from a branch".into())
+}
+
+/// Fetches from the remote of the current branch, returning the name of the remote along
+/// with the commit of its matching branch when the remote has one
+pub fn fetch(repo: &Repository) -> Result<(String, Option<Oid>), Box<dyn Error>> {
+    let reference = head_reference(repo)?;
+    let name = remote_name(repo, &reference);
+    let mut remote = repo
+        .find_remote(&name)
+        .map_err(|err| format!("unable to fetch: no remote named '{name}' is configured: {err}"))?;
+    remote
+        .fetch::<&str>(
+            &[],
+            Some(FetchOptions::new().remote_callbacks(callbacks(repo)?)),
+            None,
+        )
+        .map_err(|err| format!("unable to fetch from '{name}': {err}"))?;
+
+    let tracking = repo
+        .branch_upstream_name(&reference)
+        .ok()
+        .and_then(|tracking| tracking.as_str().map(str::to_owned))
+        .unwrap_or_else(|| {
+            let branch = reference.trim_start_matches("refs/heads/");
+            format!("refs/remotes/{name}/{branch}")
+        });
+    Ok((name, repo.refname_to_id(&tracking).ok()))
+}
+
+/// How local commits are combined with those of a remote
+#[derive(Clone, Copy)]
+pub enum Strategy {
+    Rebase,
+    Merge,
+}
+
+/// Outcome of combining local commits with those of a remote
+pub enum Integration {
+    UpToDate,
+    FastForward,
+    Rebased(usize),
+    Merged,
+    Conflicts(Vec<PathBuf>),
+}
+
+/// Combines the commits of the current branch with an upstream commit. A rebase that
+/// conflicts is aborted so that the branch is left as it was, whereas a merge that
+/// conflicts is left in progress with conflict markers in the working tree.
+pub fn integrate(
+    repo: &Repository,
+    upstream: Oid,
+    strategy: Strategy,
+    message: &str,
+) -> Result<Integration, Box<dyn Error>> {
+    let annotated = repo.find_annotated_commit(upstream)?;
+    let (analysis, _) = repo.merge_analysis(&[&annotated])?;
+    if analysis.is_up_to_date() {
+        return Ok(Integration::UpToDate);
+    }
+    if analysis.is_fast_forward() || analysis.is_unborn() {
+        let commit = repo.find_commit(upstream)?;
+        repo.checkout_tree(commit.as_object(), Some(CheckoutBuilder::new().safe()))?;
+        repo.reference(
+            &head_reference(repo)?,
+            upstream,
+            true,
+            "notes: fast-forward",
+        )?;
+        return Ok(Integration::FastForward);
+    }
+
+    match strategy {
+        Strategy::Rebase => {
+            let signature = signature(repo)?;
+            let mut rebase = repo.rebase(None, Some(&annotated), None, None)?;
+            let mut rebased = 0;
+            while let Some(operation) = rebase.next() {
+                let conflicts = match operation {
+                    Ok(_) => resolve(repo)?,
+                    Err(err) => {
+                        rebase.abort()?;
+                        return Err(format!("unable to rebase: {err}").into());
+                    }
+                };
+                if !conflicts.is_empty() {
+                    rebase.abort()?;
+                    return Ok(Integration::Conflicts(conflicts));
+                }
+                match rebase.commit(None, &signature, None) {
+                    Ok(_) => rebased += 1,
+                    // already applied upstream
+                    Err(err) if err.code() == ErrorCode::Applied => {}
+                    Err(err) => {
+                        rebase.abort()?;
+                        return Err(format!("unable to rebase: {err}").into());
+                    }
+                }
+            }
+            rebase.finish(Some(&signature))?;
+            Ok(Integration::Rebased(rebased))
+        }
+        Strategy::Merge => {
+            repo.merge(
+                &[&annotated],
+                None,
+                Some(CheckoutBuilder::new().safe().allow_conflicts(true)),
+            )?;
+            let conflicts = resolve(repo)?;
+            if !conflicts.is_empty() {
+                return Ok(Integration::Conflicts(conflicts));
+            }
+            commit_merge(repo, message)?;
+            Ok(Integration::Merged)
+        }
+    }
+}
+
+/// Resolves conflicting notes by merging their front matter field by field, writing each
+/// merged note into the working tree and returning the files that still conflict
+fn resolve(repo: &Repository) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let workdir = repo.workdir().ok_or("the repository has no working tree")?;
+    let mut index = repo.index()?;
+    let conflicts = index.conflicts()?.collect::<Result<Vec<_>, _>>()?;
+    let content = |entry: &IndexEntry| -> Result<String, git2::Error> {
+        let blob = repo.find_blob(entry.id)?;
+        Ok(String::from_utf8_lossy(blob.content()).into_owned())
+    };
+
+    let mut remaining = Vec::new();
+    for conflict in conflicts {
+        let Some(entry) = conflict.our.as_ref().or(conflict.their.as_ref()) else {
+            continue;
+        };
+        let path = PathBuf::from(String::from_utf8_lossy(&entry.path).into_owned());
+        // notes removed on one side are left for the user to decide on
+        let (Some(ours), Some(theirs)) = (&conflict.our, &conflict.their) else {
+            remaining.push(path);
+            continue;
+        };
+        if path.extension().is_none_or(|ext| ext != "md") {
+            remaining.push(path);
+            continue;
+        }
+        let ancestor = conflict.ancestor.as_ref().map(content).transpose()?;
+        let (merged, conflicted) = merge::note(
+            repo,
+            &ancestor.unwrap_or_default(),
+            &content(ours)?,
+            &content(theirs)?,
+        )?;
+        std::fs::write(workdir.join(&path), merged)?;
+        if conflicted {
+            remaining.push(path);
+        } else {
+            index.add_path(&path)?;
+        }
+    }
+    index.write()?;
+    Ok(remaining)
+}
+
+/// Whether a merge is in progress after stopping on conflicts
+pub fn merging(repo: &Repository) -> bool {
+    repo.state() == RepositoryState::Merge
+}
+
+/// Files with unresolved conflicts in the index
+pub fn conflicts(repo: &Repository) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let mut paths = Vec::new();
+    for conflict in repo.index()?.conflicts()? {
+        let conflict = conflict?;
+        if let Some(entry) = conflict.our.or(conflict.their).or(conflict.ancestor) {
+            paths.push(PathBuf::from(
+                String::from_utf8_lossy(&entry.path).into_owned(),
+            ));
+        }
+    }
+    Ok(paths)
+}
+
+/// Commits a merge in progress with the staged changes and concludes it
+pub fn commit_merge(repo: &Repository, message: &str) -> Result<Oid, Box<dyn Error>> {
+    let signature = signature(repo)?;
+    let tree = repo.find_tree(repo.index()?.write_tree()?)?;
+    let parents = [
+        repo.head()?.peel_to_commit()?,
+        repo.find_commit(repo.refname_to_id("MERGE_HEAD")?)?,
+    ];
+    let oid = repo
+        .commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            message,
+            &tree,
+            &Vec::from_iter(parents.iter()),
+        )
+        .map_err(|err| format!("unable to commit the merge: {err}"))?;
+    repo.cleanup_state()?;
+    Ok(oid)
+}
+
+/// Abandons a merge in progress, restoring the last commit
+pub fn abort(repo: &Repository) -> Result<(), Box<dyn Error>> {
+    let head = repo.head()?.peel_to_commit()?;
+    repo.reset(head.as_object(), ResetType::Hard, None)?;
+    repo.cleanup_state()?;
+    Ok(())
+}
+
+/// Callbacks that authenticate with remotes through the ssh agent, ssh keys or the git
+/// credential helper
+pub fn callbacks<'a>(repo: &Repository) -> Result<RemoteCallbacks<'a>, Box<dyn Error>> {
+    let config = repo.config()?;
+    let home = std::env::var("HOME").map(PathBuf::from).ok();
+    let keys = Vec::from_iter(
+        SSH_KEYS
+            .iter()
+            .filter_map(|key| Some(home.as_ref()?.join(".ssh").join(key)))
+            .filter(|key| key.exists()),
+    );
+
+    let mut attempt = 0;
+    let mut callbacks = RemoteCallbacks::new();
+    callbacks.credentials(move |url, username, allowed| {
+        attempt += 1;
+        let username = username.unwrap_or("git");
+        if allowed.contains(CredentialType::SSH_KEY) {
+            // the agent is tried first, followed by each key on disk
+            return match attempt {
+                1 => Cred::ssh_key_from_agent(username),
+                n => match keys.get(n - 2) {
+                    Some(key) => Cred::ssh_key(username, None, key, None),
+                    None => Err(git2::Error::from_str("no ssh key was accepted")),
+                },
+            };
+        }
+        if attempt > 1 {
+            return Err(git2::Error::from_str("credentials were rejected"));
+        }
+        if allowed.contains(CredentialType::USER_PASS_PLAINTEXT) {
+            return Cred::credential_helper(&config, url, Some(username));
+        }
+        if allowed.contains(CredentialType::USERNAME) {
+            return Cred::username(username);
+        }
+        Cred::default()
+    });
+    Ok(callbacks)
+}
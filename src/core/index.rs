@@ -0,0 +1,97 @@
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    fs::File,
+    io::BufReader,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{frontmatter, markdown::NotesFrontMatter, resolve};
+
+pub const CACHE_DIR: &str = ".notes-cache";
+const CACHE_FILE: &str = "index.json";
+
+/// Indexed representation of a note
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub(crate) struct IndexEntry {
+    pub(crate) size: u64,
+    pub(crate) modified: SystemTime,
+    pub(crate) frontmatter: NotesFrontMatter,
+    // number of lines taken up by the front matter, after which the body starts
+    pub(crate) offset: usize,
+}
+
+/// Index of the front matter of every note within a note stack
+#[derive(Deserialize, Serialize, Default, Debug)]
+pub(crate) struct Index {
+    pub(crate) entries: BTreeMap<PathBuf, IndexEntry>,
+}
+
+impl Index {
+    /// Loads the cached index of a note stack, starting afresh when there is no usable cache
+    pub fn load(root: &Path) -> Self {
+        std::fs::read(root.join(CACHE_DIR).join(CACHE_FILE))
+            .ok()
+            .and_then(|buf| serde_json::from_slice(&buf).ok())
+            .unwrap_or_default()
+    }
+
+    /// Persists the index within the cache directory of a note stack
+    pub fn save(&self, root: &Path) -> Result<(), Box<dyn Error>> {
+        let dir = root.join(CACHE_DIR);
+        std::fs::create_dir_all(&dir)?;
+        // the cache ignores itself so that it is never committed, whatever the stack ignores
+        let ignore = dir.join(".gitignore");
+        if !ignore.try_exists()? {
+            std::fs::write(ignore, "*\n")?;
+        }
+        // written aside and renamed so that concurrent readers never see a partial cache
+        let tmp = dir.join(format!("{CACHE_FILE}.{}", std::process::id()));
+        std::fs::write(&tmp, serde_json::to_vec(self)?)?;
+        std::fs::rename(tmp, dir.join(CACHE_FILE))?;
+        Ok(())
+    }
+
+    /// Brings the index up to date with the note stack, only parsing notes that changed
+    /// since they were last indexed. Returns whether the index was modified.
+    pub fn refresh(&mut self, root: &Path) -> Result<bool, Box<dyn Error>> {
+        let notes = resolve::notes(root)?;
+        let count = self.entries.len();
+        self.entries.retain(|p, _| notes.binary_search(p).is_ok());
+        let mut changed = count != self.entries.len();
+
+        for path in notes {
+            let Ok(metadata) = std::fs::metadata(&path) else {
+                // removed since the note stack was walked
+                continue;
+            };
+            let (size, modified) = (metadata.len(), metadata.modified()?);
+            if self
+                .entries
+                .get(&path)
+                .is_some_and(|e| e.size == size && e.modified == modified)
+            {
+                continue;
+            }
+
+            let mut reader = BufReader::new(File::open(&path)?);
+            let buf = frontmatter::fetch(&mut reader)?;
+            let frontmatter =
+                frontmatter::generate(&buf).map_err(|err| format!("{}: {err}", path.display()))?;
+            self.entries.insert(
+                path,
+                IndexEntry {
+                    size,
+                    modified,
+                    frontmatter,
+                    offset: buf.lines().count() + 2,
+                },
+            );
+            changed = true;
+        }
+        Ok(changed)
+    }
+}
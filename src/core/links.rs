@@ -0,0 +1,92 @@
+use std::{error::Error, path::Path, sync::LazyLock};
+
+use regex::Regex;
+
+use super::frontmatter;
+
+/// A link found within the body of a note
+pub struct Link {
+    pub(crate) target: String,
+    pub(crate) line: usize,
+}
+
+/// Reads the body of a note along with the number of lines that precede it
+pub fn body(path: &Path) -> Result<(String, usize), Box<dyn Error>> {
+    let contents = std::fs::read_to_string(path)?;
+    match frontmatter::split(&contents) {
+        Some((yaml, body)) => Ok((body.to_owned(), yaml.lines().count() + 2)),
+        None => Ok((contents, 0)),
+    }
+}
+
+/// Extracts wiki-style links such as `[[note]]`, `[[note|alias]]` and `[[note#heading]]`,
+/// ignoring fenced code blocks
+pub fn wiki(body: &str, offset: usize) -> Vec<Link> {
+    let mut links = Vec::new();
+    for (i, line) in lines(body) {
+        let mut rest = line;
+        while let Some(start) = rest.find("[[") {
+            let inner = &rest[start + 2..];
+            let Some(end) = inner.find("]]") else {
+                break;
+            };
+            let target = inner[..end]
+                .split(['|', '#'])
+                .next()
+                .unwrap_or_default()
+                .trim();
+            if !target.is_empty() {
+                links.push(Link {
+                    target: target.to_owned(),
+                    line: offset + i + 1,
+                });
+            }
+            rest = &inner[end + 2..];
+        }
+    }
+    links
+}
+
+/// Lines of a note body outside of fenced code blocks
+pub(crate) fn lines(body: &str) -> impl Iterator<Item = (usize, &str)> {
+    let mut fenced = false;
+    body.lines().enumerate().filter(move |(_, line)| {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            fenced = !fenced;
+            return false;
+        }
+        !fenced
+    })
+}
+
+static MARKDOWN_LINK: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"\[[^\]]*\]\(\s*<?([^)\s>]+)>?(?:\s+"[^"]*")?\s*\)"#)
+        .expect("markdown link pattern is valid")
+});
+
+/// Extracts the targets of relative markdown links such as `[text](other.md)`, ignoring
+/// urls, anchors within the note and fenced code blocks
+pub fn markdown(body: &str, offset: usize) -> Vec<Link> {
+    let mut links = Vec::new();
+    for (i, line) in lines(body) {
+        for capture in MARKDOWN_LINK.captures_iter(line) {
+            let target = &capture[1];
+            if target.starts_with('#') || target.starts_with('/') || target.contains("://") {
+                continue;
+            }
+            if let Some((scheme, _)) = target.split_once(':')
+                && scheme.chars().all(|c| c.is_ascii_alphabetic())
+            {
+                // mailto: and similar schemes
+                continue;
+            }
+            let target = target.split(['#', '?']).next().unwrap_or_default();
+            links.push(Link {
+                target: target.replace("%20", " "),
+                line: offset + i + 1,
+            });
+        }
+    }
+    links
+}
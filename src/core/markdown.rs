@@ -1,6 +1,6 @@
 use std::{error::Error, fs::OpenOptions, io::Write};
 
-use chrono::{DateTime, Local};
+use chrono::{DateTime, Local, NaiveDate};
 use serde::{Deserialize, Serialize};
 
 /// Representation of a markdown file
@@ -9,10 +9,11 @@ pub struct File<'a> {
     path: &'a str,
     category: Option<&'a str>,
     tags: Option<Vec<String>>,
+    body: Option<&'a str>,
 }
 
 /// Representation of the yaml metadata field
-#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
 pub(crate) struct Metadata {
     pub(crate) category: Option<String>,
     pub(crate) subcategories: Option<Vec<String>>,
@@ -20,7 +21,7 @@ pub(crate) struct Metadata {
 }
 
 impl Metadata {
-    fn new(category: Option<&str>) -> Self {
+    pub(crate) fn new(category: Option<&str>) -> Self {
         Self {
             category: category.map(|s| s.split("/").take(1).next().unwrap().into()),
             subcategories: category.map(|s| {
@@ -36,7 +37,7 @@ impl Metadata {
 }
 
 /// Representation of the front matter at the top of each note
-#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
 pub(crate) struct NotesFrontMatter {
     pub(crate) title: String,
     pub(crate) date: String,
@@ -67,12 +68,14 @@ impl<'a> File<'a> {
         path: &'a str,
         category: Option<&'a str>,
         tags: Option<Vec<String>>,
+        body: Option<&'a str>,
     ) -> Self {
         Self {
             name,
             path,
             category,
             tags,
+            body,
         }
     }
 
@@ -84,18 +87,31 @@ impl<'a> File<'a> {
             .open(self.path)?;
 
         let mut writer = std::io::BufWriter::new(file);
-        let title = if DateTime::parse_from_rfc3339(self.name).is_ok() {
-            self.name.to_string()
-        } else {
-            self.name.replace("-", " ")
-        };
-        let frontmatter =
-            NotesFrontMatter::new(title, self.category, self.tags, Local::now().to_rfc3339());
+        let frontmatter = NotesFrontMatter::new(
+            title(self.name),
+            self.category,
+            self.tags,
+            Local::now().to_rfc3339(),
+        );
         let frontmatter_str = serde_yaml_ng::to_string(&frontmatter)?;
         writer.write_all(b"---\n")?;
         writer.write_all(frontmatter_str.as_bytes())?;
         writer.write_all(b"---\n\n")?;
+        if let Some(body) = self.body {
+            writer.write_all(body.as_bytes())?;
+        }
         writer.flush()?;
         Ok(())
     }
 }
+
+/// Derives the title of a note from its filename, keeping date names intact
+pub(crate) fn title(name: &str) -> String {
+    if DateTime::parse_from_rfc3339(name).is_ok()
+        || NaiveDate::parse_from_str(name, "%Y-%m-%d").is_ok()
+    {
+        name.to_string()
+    } else {
+        name.replace("-", " ")
+    }
+}
@@ -0,0 +1,363 @@
+use std::error::Error;
+
+use chrono::{DateTime, FixedOffset};
+use git2::{IndexEntry, IndexTime, MergeFileOptions, Repository};
+use serde_yaml_ng::{Mapping, Value};
+
+use super::frontmatter;
+
+const TAGS: &str = "tags";
+const DATE: &str = "date";
+// appended on a line of its own, whether or not the text ends with a newline
+const TRAILER: &str = "\n\u{1}notes merge trailer";
+
+/// Three-way merges two versions of a note. The front matter is merged field by field,
+/// keeping tags added on either side and the latest date, while only the body is merged as
+/// text. The merged note is returned along with whether the body still has conflicts.
+pub fn note(
+    repo: &Repository,
+    ancestor: &str,
+    ours: &str,
+    theirs: &str,
+) -> Result<(String, bool), Box<dyn Error>> {
+    let (Some((our_yaml, our_body)), Some((their_yaml, their_body))) =
+        (frontmatter::split(ours), frontmatter::split(theirs))
+    else {
+        return text(repo, ancestor, ours, theirs);
+    };
+    // a note added on both sides has no common front matter
+    let (base_yaml, base_body) = frontmatter::split(ancestor).unwrap_or(("", ancestor));
+    let (Ok(base), Ok(our_yaml), Ok(their_yaml)) = (
+        frontmatter::mapping(base_yaml),
+        frontmatter::mapping(our_yaml),
+        frontmatter::mapping(their_yaml),
+    ) else {
+        return text(repo, ancestor, ours, theirs);
+    };
+
+    let yaml = fields(&base, &our_yaml, &their_yaml);
+    let (body, conflicted) = text(repo, base_body, our_body, their_body)?;
+    Ok((
+        format!(
+            "---\n{}---\n{body}",
+            serde_yaml_ng::to_string(&Value::Mapping(yaml))?
+        ),
+        conflicted,
+    ))
+}
+
+/// Merges front matter key by key. Where both sides changed the same key, the side with the
+/// later date wins.
+fn fields(base: &Mapping, ours: &Mapping, theirs: &Mapping) -> Mapping {
+    let theirs_newer = date(theirs) > date(ours);
+    let mut merged = mapping(base, ours, theirs, theirs_newer);
+
+    let newest = if theirs_newer { theirs } else { ours };
+    if let Some(date) = newest.get(DATE).or_else(|| ours.get(DATE)) {
+        merged.insert(DATE.into(), date.clone());
+    }
+
+    let tags = tags(base.get(TAGS), ours.get(TAGS), theirs.get(TAGS));
+    if merged.contains_key(TAGS) || !tags.is_empty() {
+        let tags = match tags.is_empty() {
+            true => Value::Null,
+            false => Value::Sequence(Vec::from_iter(tags.into_iter().map(Value::String))),
+        };
+        merged.insert(TAGS.into(), tags);
+    }
+    merged
+}
+
+/// Three-way merge of yaml mappings, keeping the order of our keys
+fn mapping(base: &Mapping, ours: &Mapping, theirs: &Mapping, theirs_newer: bool) -> Mapping {
+    let mut merged = Mapping::new();
+    let keys = ours
+        .keys()
+        .chain(theirs.keys().filter(|key| !ours.contains_key(*key)));
+    for key in keys {
+        if let Some(value) = value(base.get(key), ours.get(key), theirs.get(key), theirs_newer) {
+            merged.insert(key.clone(), value);
+        }
+    }
+    merged
+}
+
+/// Three-way merge of a yaml value, where a missing value is one that was removed
+fn value(
+    base: Option<&Value>,
+    ours: Option<&Value>,
+    theirs: Option<&Value>,
+    theirs_newer: bool,
+) -> Option<Value> {
+    if ours == theirs || theirs == base {
+        return ours.cloned();
+    }
+    if ours == base {
+        return theirs.cloned();
+    }
+    if let (Some(Value::Mapping(ours)), Some(Value::Mapping(theirs))) = (ours, theirs) {
+        let base = match base {
+            Some(Value::Mapping(base)) => base,
+            _ => &Mapping::new(),
+        };
+        return Some(Value::Mapping(mapping(base, ours, theirs, theirs_newer)));
+    }
+    if theirs_newer {
+        theirs.cloned()
+    } else {
+        ours.cloned()
+    }
+}
+
+/// Union of the tags on both sides, less any tag that either side removed
+fn tags(base: Option<&Value>, ours: Option<&Value>, theirs: Option<&Value>) -> Vec<String> {
+    let list = |tags: Option<&Value>| match tags {
+        Some(Value::Sequence(tags)) => {
+            Vec::from_iter(tags.iter().filter_map(|t| t.as_str().map(str::to_owned)))
+        }
+        _ => Vec::new(),
+    };
+    let (base, ours, theirs) = (list(base), list(ours), list(theirs));
+    let mut merged = Vec::new();
+    for tag in ours.iter().chain(theirs.iter()) {
+        let removed = base.contains(tag) && !(ours.contains(tag) && theirs.contains(tag));
+        if !removed && !merged.contains(tag) {
+            merged.push(tag.clone());
+        }
+    }
+    merged
+}
+
+fn date(yaml: &Mapping) -> Option<DateTime<FixedOffset>> {
+    DateTime::parse_from_rfc3339(yaml.get(DATE)?.as_str()?).ok()
+}
+
+/// Three-way merges text with conflict markers wherever both sides changed the same lines
+fn text(
+    repo: &Repository,
+    ancestor: &str,
+    ours: &str,
+    theirs: &str,
+) -> Result<(String, bool), Box<dyn Error>> {
+    if ours == theirs || theirs == ancestor {
+        return Ok((ours.to_owned(), false));
+    }
+    if ours == ancestor {
+        return Ok((theirs.to_owned(), false));
+    }
+
+    // git2 cannot read an empty merge result, so every side gets an unchanged last line that
+    // is removed again after merging
+    let entry = |contents: &str| -> Result<IndexEntry, git2::Error> {
+        let contents = format!("{contents}{TRAILER}");
+        Ok(IndexEntry {
+            ctime: IndexTime::new(0, 0),
+            mtime: IndexTime::new(0, 0),
+            dev: 0,
+            ino: 0,
+            mode: 0o100644,
+            uid: 0,
+            gid: 0,
+            file_size: contents.len() as u32,
+            id: repo.blob(contents.as_bytes())?,
+            flags: 0,
+            flags_extended: 0,
+            path: b"note.md".to_vec(),
+        })
+    };
+    let mut options = MergeFileOptions::new();
+    options.our_label("ours").their_label("theirs");
+    let result = repo.merge_file_from_index(
+        &entry(ancestor)?,
+        &entry(ours)?,
+        &entry(theirs)?,
+        Some(&mut options),
+    )?;
+    let merged = String::from_utf8_lossy(result.content());
+    Ok((
+        merged
+            .strip_suffix(TRAILER)
+            .ok_or("unable to merge the body of the note")?
+            .to_owned(),
+        !result.is_automergeable(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    /// A throwaway repository for the blobs written while merging text
+    struct Scratch(PathBuf, Repository);
+
+    impl Scratch {
+        fn new(name: &str) -> Self {
+            let dir =
+                std::env::temp_dir().join(format!("notes-merge-{name}-{}", std::process::id()));
+            let _ = std::fs::remove_dir_all(&dir);
+            let repo = Repository::init_bare(&dir).unwrap();
+            Self(dir, repo)
+        }
+    }
+
+    impl Drop for Scratch {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn yaml(buf: &str) -> Mapping {
+        frontmatter::mapping(buf).unwrap()
+    }
+
+    fn sample(date: &str, tags: &str, body: &str) -> String {
+        format!("---\ntitle: Note\ndate: {date}\ntags: {tags}\n---\n{body}")
+    }
+
+    #[test]
+    fn merges_tags_and_date_of_notes_without_a_body() {
+        let scratch = Scratch::new("empty-body");
+        let (merged, conflicted) = note(
+            &scratch.1,
+            &sample("2026-01-01T00:00:00+00:00", "[a]", ""),
+            &sample("2026-01-02T00:00:00+00:00", "[a, b]", ""),
+            &sample("2026-01-03T00:00:00+00:00", "[a, c]", ""),
+        )
+        .unwrap();
+        assert!(!conflicted);
+        assert_eq!(
+            merged,
+            "---\ntitle: Note\ndate: 2026-01-03T00:00:00+00:00\ntags:\n- a\n- b\n- c\n---\n"
+        );
+    }
+
+    #[test]
+    fn merges_front_matter_and_body_separately() {
+        let scratch = Scratch::new("note");
+        let date = "2026-01-01T00:00:00+00:00";
+        let (merged, conflicted) = note(
+            &scratch.1,
+            &sample(date, "[a]", "one\ntwo\nthree\n"),
+            &sample(date, "[a, b]", "one changed\ntwo\nthree\n"),
+            &sample(date, "[]", "one\ntwo\nthree changed\n"),
+        )
+        .unwrap();
+        assert!(!conflicted);
+        assert_eq!(
+            merged,
+            format!(
+                "---\ntitle: Note\ndate: {date}\ntags:\n- b\n---\none changed\ntwo\nthree changed\n"
+            )
+        );
+    }
+
+    #[test]
+    fn reports_conflicting_bodies() {
+        let scratch = Scratch::new("conflict");
+        let date = "2026-01-01T00:00:00+00:00";
+        let (merged, conflicted) = note(
+            &scratch.1,
+            &sample(date, "[a]", "line\n"),
+            &sample(date, "[a]", "ours\n"),
+            &sample(date, "[a]", "theirs\n"),
+        )
+        .unwrap();
+        assert!(conflicted);
+        assert!(merged.starts_with(&format!(
+            "---\ntitle: Note\ndate: {date}\ntags:\n- a\n---\n"
+        )));
+        assert!(merged.contains("<<<<<<< ours\nours\n=======\ntheirs\n>>>>>>> theirs\n"));
+    }
+
+    #[test]
+    fn merges_notes_without_front_matter_as_text() {
+        let scratch = Scratch::new("plain");
+        let (merged, conflicted) = note(
+            &scratch.1,
+            "a\nb\nc\n",
+            "a changed\nb\nc\n",
+            "a\nb\nc changed\n",
+        )
+        .unwrap();
+        assert!(!conflicted);
+        assert_eq!(merged, "a changed\nb\nc changed\n");
+    }
+
+    #[test]
+    fn fields_take_changes_from_either_side() {
+        let base = yaml("title: Note\nstatus: draft\nkeep: yes\n");
+        let ours = yaml("title: Renamed\nstatus: draft\nkeep: yes\n");
+        let theirs = yaml("title: Note\nstatus: done\nadded: true\n");
+        assert_eq!(
+            fields(&base, &ours, &theirs),
+            yaml("title: Renamed\nstatus: done\nadded: true\n")
+        );
+    }
+
+    #[test]
+    fn fields_prefer_the_later_side_when_both_change_a_key() {
+        let base = yaml("date: 2026-01-01T00:00:00+00:00\nstatus: draft\n");
+        let ours = yaml("date: 2026-01-03T00:00:00+00:00\nstatus: ours\n");
+        let theirs = yaml("date: 2026-01-02T00:00:00+00:00\nstatus: theirs\n");
+        assert_eq!(fields(&base, &ours, &theirs), ours);
+        assert_eq!(fields(&base, &theirs, &ours), ours);
+    }
+
+    #[test]
+    fn fields_merge_nested_mappings() {
+        let base = yaml("meta:\n  category: a\n  hidden: false\n");
+        let ours = yaml("meta:\n  category: b\n  hidden: false\n");
+        let theirs = yaml("meta:\n  category: a\n  hidden: true\n");
+        assert_eq!(
+            fields(&base, &ours, &theirs),
+            yaml("meta:\n  category: b\n  hidden: true\n")
+        );
+    }
+
+    #[test]
+    fn tags_are_united_less_removed_tags() {
+        let list = |tags: &str| serde_yaml_ng::from_str::<Value>(tags).unwrap();
+        assert_eq!(
+            tags(
+                Some(&list("[a, b]")),
+                Some(&list("[a, b, c]")),
+                Some(&list("[b, d]"))
+            ),
+            vec!["b", "c", "d"]
+        );
+        assert_eq!(tags(None, None, Some(&list("[a]"))), vec!["a"]);
+        assert!(tags(Some(&list("[a]")), Some(&list("[a]")), Some(&Value::Null)).is_empty());
+    }
+
+    #[test]
+    fn dates_are_read_as_rfc3339() {
+        assert_eq!(
+            date(&yaml("date: 2026-01-02T03:04:05+01:00\n")),
+            DateTime::parse_from_rfc3339("2026-01-02T03:04:05+01:00").ok()
+        );
+        assert_eq!(date(&yaml("date: yesterday\n")), None);
+        assert_eq!(date(&yaml("title: Note\n")), None);
+    }
+
+    #[test]
+    fn text_merges_without_trailing_newlines_or_bodies() {
+        let scratch = Scratch::new("text");
+        let repo = &scratch.1;
+        assert_eq!(
+            text(repo, "a\nb\nc", "x\nb\nc", "a\nb\ny").unwrap(),
+            ("x\nb\ny".to_owned(), false)
+        );
+        assert_eq!(
+            text(repo, "a\n\nb\n\nc\n", "\nb\n\nc\n", "a\n\nb\n\n")
+                .unwrap()
+                .0,
+            "\nb\n\n"
+        );
+        assert_eq!(
+            text(repo, "a\n", "", "a\n").unwrap(),
+            (String::new(), false)
+        );
+    }
+}
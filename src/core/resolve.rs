@@ -0,0 +1,262 @@
+use std::{
+    collections::VecDeque,
+    error::Error,
+    fs::File,
+    io::BufReader,
+    path::{Component, Path, PathBuf},
+};
+
+use super::frontmatter;
+
+/// Collects the path of every note within a note stack, skipping hidden directories.
+pub fn notes(root: &Path) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let mut dequeue = VecDeque::new();
+    let mut notes = Vec::new();
+    dequeue.push_back(root.to_path_buf());
+    while let Some(entry) = dequeue.pop_front() {
+        for child in std::fs::read_dir(entry)? {
+            let child = child?;
+            if child.file_name().to_string_lossy().starts_with(".") {
+                continue;
+            }
+            let path = child.path();
+            if path.is_dir() {
+                dequeue.push_back(path);
+            } else if path.extension().is_some_and(|ext| ext == "md") {
+                notes.push(path);
+            }
+        }
+    }
+    notes.sort();
+    Ok(notes)
+}
+
+/// Resolves a note within a note stack by path, filename or title. A fuzzy match
+/// is attempted when no exact match exists.
+pub fn note(root: &Path, query: &str) -> Result<PathBuf, Box<dyn Error>> {
+    if let Some(path) = by_path(root, query)? {
+        return Ok(path);
+    }
+
+    let notes = notes(root)?;
+    let name = query.strip_suffix(".md").unwrap_or(query);
+    let matches: Vec<&PathBuf> = notes
+        .iter()
+        .filter(|p| p.file_stem().is_some_and(|s| s == name))
+        .collect();
+    if let Some(path) = unique(root, query, &matches)? {
+        return Ok(path);
+    }
+
+    let titles: Vec<(&PathBuf, String)> = notes
+        .iter()
+        .filter_map(|p| title(p).map(|t| (p, t)))
+        .collect();
+    let matches: Vec<&PathBuf> = titles
+        .iter()
+        .filter(|(_, t)| t.eq_ignore_ascii_case(query) || *t == query.replace("-", " "))
+        .map(|(p, _)| *p)
+        .collect();
+    if let Some(path) = unique(root, query, &matches)? {
+        return Ok(path);
+    }
+
+    fuzzy(root, query, &titles)
+}
+
+/// Resolves a query as a path relative to the note stack or the working directory
+fn by_path(root: &Path, query: &str) -> Result<Option<PathBuf>, Box<dyn Error>> {
+    let canonical_root = root.canonicalize()?;
+    let candidates = [
+        root.join(query),
+        root.join(format!("{query}.md")),
+        PathBuf::from(query),
+    ];
+    for candidate in candidates {
+        if !candidate.is_file() || candidate.extension().is_none_or(|ext| ext != "md") {
+            continue;
+        }
+        if let Ok(relative) = candidate.canonicalize()?.strip_prefix(&canonical_root) {
+            return Ok(Some(root.join(relative)));
+        }
+    }
+    Ok(None)
+}
+
+/// Returns the single match, or an error listing every candidate when ambiguous
+fn unique(
+    root: &Path,
+    query: &str,
+    matches: &[&PathBuf],
+) -> Result<Option<PathBuf>, Box<dyn Error>> {
+    match matches {
+        [] => Ok(None),
+        [path] => Ok(Some(path.to_path_buf())),
+        _ => Err(ambiguous(root, query, matches.iter().copied())),
+    }
+}
+
+fn ambiguous<'a>(
+    root: &Path,
+    query: &str,
+    matches: impl Iterator<Item = &'a PathBuf>,
+) -> Box<dyn Error> {
+    let candidates = matches
+        .map(|p| relative(root, p))
+        .collect::<Vec<_>>()
+        .join("\n  ");
+    format!("note '{query}' is ambiguous, candidates are:\n  {candidates}").into()
+}
+
+/// Fuzzy matches a query against the relative path and title of every note,
+/// preferring the tightest match.
+fn fuzzy(
+    root: &Path,
+    query: &str,
+    notes: &[(&PathBuf, String)],
+) -> Result<PathBuf, Box<dyn Error>> {
+    let query = query.to_lowercase();
+    let mut scored: Vec<(usize, &PathBuf)> = notes
+        .iter()
+        .filter_map(|(path, title)| {
+            let mut name = relative(root, path);
+            name.truncate(name.len() - ".md".len());
+            [
+                score(&query, &name.to_lowercase()),
+                score(&query, &title.to_lowercase()),
+            ]
+            .into_iter()
+            .flatten()
+            .min()
+            .map(|s| (s, *path))
+        })
+        .collect();
+    scored.sort();
+
+    match scored.as_slice() {
+        [] => Err(format!("no note matching '{query}'").into()),
+        [(_, path)] => Ok(path.to_path_buf()),
+        [(best, path), (next, _), ..] if best < next => Ok(path.to_path_buf()),
+        [(best, _), ..] => {
+            let best = *best;
+            Err(ambiguous(
+                root,
+                &query,
+                scored
+                    .iter()
+                    .take_while(|(s, _)| *s == best)
+                    .map(|(_, p)| *p),
+            ))
+        }
+    }
+}
+
+/// Scores a subsequence match of the query within the candidate by the number of
+/// skipped characters. Lower scores are better.
+fn score(query: &str, candidate: &str) -> Option<usize> {
+    let mut chars = candidate.chars();
+    let mut skipped = 0;
+    let mut started = false;
+    for q in query.chars() {
+        loop {
+            let c = chars.next()?;
+            if c == q {
+                started = true;
+                break;
+            }
+            if started {
+                skipped += 1;
+            }
+        }
+    }
+    Some(skipped)
+}
+
+/// Fetches the title of a note, ignoring notes with malformed front matter
+fn title(path: &Path) -> Option<String> {
+    let mut reader = BufReader::new(File::open(path).ok()?);
+    let buf = frontmatter::fetch(&mut reader).ok()?;
+    frontmatter::generate(&buf).ok().map(|f| f.title)
+}
+
+/// Path of a note relative to the root of the note stack
+pub fn relative(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .to_string()
+}
+
+/// Every note within a note stack along with its title, used to resolve links between notes
+pub struct Catalog {
+    root: PathBuf,
+    notes: Vec<(PathBuf, Option<String>)>,
+}
+
+impl Catalog {
+    pub fn new(root: &Path) -> Result<Self, Box<dyn Error>> {
+        Ok(Self {
+            root: root.to_path_buf(),
+            notes: notes(root)?
+                .into_iter()
+                .map(|p| {
+                    let title = title(&p);
+                    (p, title)
+                })
+                .collect(),
+        })
+    }
+
+    /// Paths of every note within the note stack
+    pub fn notes(&self) -> impl Iterator<Item = &Path> {
+        self.notes.iter().map(|(p, _)| p.as_path())
+    }
+
+    /// Resolves the target of a link within a note by relative path, filename or title
+    pub fn link(&self, from: &Path, target: &str) -> Option<&Path> {
+        let target = target.strip_suffix(".md").unwrap_or(target);
+        let file = format!("{target}.md");
+
+        // relative to the linking note first, then to the root of the note stack
+        for base in [from.parent(), Some(self.root.as_path())]
+            .into_iter()
+            .flatten()
+        {
+            let candidate = normalise(&base.join(&file));
+            if let Some((path, _)) = self.notes.iter().find(|(p, _)| *p == candidate) {
+                return Some(path);
+            }
+        }
+
+        // the shortest path wins when several notes share a filename
+        if !target.contains('/')
+            && let Some((path, _)) = self
+                .notes
+                .iter()
+                .filter(|(p, _)| p.file_stem().is_some_and(|s| s == target))
+                .min_by_key(|(p, _)| p.components().count())
+        {
+            return Some(path);
+        }
+
+        self.notes
+            .iter()
+            .find(|(_, t)| t.as_ref().is_some_and(|t| t.eq_ignore_ascii_case(target)))
+            .map(|(p, _)| p.as_path())
+    }
+}
+
+/// Lexically resolves `.` and `..` components of a path
+pub fn normalise(path: &Path) -> PathBuf {
+    let mut normalised = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalised.pop();
+            }
+            component => normalised.push(component),
+        }
+    }
+    normalised
+}
@@ -0,0 +1,50 @@
+use std::{error::Error, path::Path};
+
+use crate::system::CONFIG_DIR;
+
+const TEMPLATE_DIR: &str = "templates";
+const STACK_TEMPLATE_DIR: &str = ".templates";
+
+/// Loads a template by name, preferring the templates of the note stack over those
+/// within the notes configuration directory
+pub fn load(root: &Path, name: &str) -> Result<String, Box<dyn Error>> {
+    let file = format!("{}.md", name.strip_suffix(".md").unwrap_or(name));
+    let candidates = [
+        root.join(STACK_TEMPLATE_DIR).join(&file),
+        Path::new(&*CONFIG_DIR).join(TEMPLATE_DIR).join(&file),
+    ];
+    for candidate in &candidates {
+        if candidate.try_exists()? {
+            return Ok(std::fs::read_to_string(candidate)?);
+        }
+    }
+    Err(format!(
+        "template '{name}' not found in '{}' or '{}'",
+        candidates[0].parent().unwrap().display(),
+        candidates[1].parent().unwrap().display()
+    )
+    .into())
+}
+
+/// Substitutes `{{placeholder}}` occurrences with their values, leaving unknown
+/// placeholders as they are
+pub fn render(template: &str, values: &[(&str, &str)]) -> String {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        rendered.push_str(&rest[..start]);
+        let inner = &rest[start + 2..];
+        let Some(end) = inner.find("}}") else {
+            rest = &rest[start..];
+            break;
+        };
+        let key = inner[..end].trim();
+        match values.iter().find(|(k, _)| *k == key) {
+            Some((_, value)) => rendered.push_str(value),
+            None => rendered.push_str(&rest[start..start + end + 4]),
+        }
+        rest = &inner[end + 2..];
+    }
+    rendered.push_str(rest);
+    rendered
+}
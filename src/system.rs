@@ -26,6 +26,9 @@ pub static CONFIG_FILE: LazyLock<String> = LazyLock::new(|| {
         .to_string()
 });
 
+/// Name of the file within a note stack that overrides `notes.toml` for that stack
+pub const STACK_CONFIG_FILE: &str = "stack.toml";
+
 use serde::{Deserialize, Serialize};
 
 #[derive(Deserialize, Serialize, Default)]
@@ -33,6 +36,9 @@ use serde::{Deserialize, Serialize};
 pub struct Configuration {
     pub(crate) settings: Settings,
     pub(crate) options: Option<Options>,
+    // keys whose value was set by the `stack.toml` of the active note stack
+    #[serde(skip)]
+    pub(crate) overrides: Vec<&'static str>,
 }
 
 #[derive(Deserialize, Serialize)]
@@ -44,6 +50,65 @@ pub(crate) struct Settings {
 #[derive(Deserialize, Serialize, Default)]
 pub(crate) struct Options {
     pub(crate) hide_root: Option<String>,
+    pub(crate) default_template: Option<String>,
+    pub(crate) journal_category: Option<String>,
+    pub(crate) default_category: Option<String>,
+}
+
+impl Options {
+    /// Every option by name along with its value
+    pub(crate) fn fields(&self) -> [(&'static str, Option<&str>); 4] {
+        [
+            ("hide_root", self.hide_root.as_deref()),
+            ("default_template", self.default_template.as_deref()),
+            ("journal_category", self.journal_category.as_deref()),
+            ("default_category", self.default_category.as_deref()),
+        ]
+    }
+
+    /// Replaces the options set in another layer, returning the name of each replaced option
+    fn layer(&mut self, other: Options) -> Vec<&'static str> {
+        let mut layered = Vec::new();
+        for (key, value, other) in [
+            ("hide_root", &mut self.hide_root, other.hide_root),
+            (
+                "default_template",
+                &mut self.default_template,
+                other.default_template,
+            ),
+            (
+                "journal_category",
+                &mut self.journal_category,
+                other.journal_category,
+            ),
+            (
+                "default_category",
+                &mut self.default_category,
+                other.default_category,
+            ),
+        ] {
+            if other.is_some() {
+                *value = other;
+                layered.push(key);
+            }
+        }
+        layered
+    }
+}
+
+/// Overrides read from the `stack.toml` of a note stack. The path of a note stack cannot be
+/// overridden.
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct StackConfiguration {
+    settings: Option<StackSettings>,
+    options: Option<Options>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct StackSettings {
+    editor: Option<String>,
 }
 
 impl Default for Settings {
@@ -103,5 +168,27 @@ fn workspace_init(mut conf: Configuration) -> Result<Configuration, Box<dyn Erro
         return Err(format!("Invalid notes stack'{}' in .notes", stack).into());
     }
 
+    stack_init(conf)
+}
+
+/// Layers the `stack.toml` of the active note stack, if any, on top of the configuration
+fn stack_init(mut conf: Configuration) -> Result<Configuration, Box<dyn Error>> {
+    let file = Path::new(&conf.settings.path).join(STACK_CONFIG_FILE);
+    let buf = match std::fs::read_to_string(&file) {
+        Ok(buf) => buf,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(conf),
+        Err(err) => return Err(err.into()),
+    };
+    let stack = toml::from_str::<StackConfiguration>(&buf)
+        .map_err(|err| format!("{}: {err}", file.display()))?;
+
+    if let Some(editor) = stack.settings.and_then(|s| s.editor) {
+        conf.settings.editor = Some(editor);
+        conf.overrides.push("editor");
+    }
+    if let Some(options) = stack.options {
+        let layered = conf.options.get_or_insert_default().layer(options);
+        conf.overrides.extend(layered);
+    }
     Ok(conf)
 }
@@ -0,0 +1,250 @@
+//! Runs `notes sync` against a local bare repository standing in for the remote, with a clone
+//! of the remote standing in for another machine.
+
+use std::{
+    path::{Path, PathBuf},
+    process::{Command, Output},
+};
+
+use git2::{Oid, Repository, RepositoryState, Signature};
+
+/// A home directory holding a note stack, a bare remote and a clone of the remote
+struct Workspace {
+    dir: PathBuf,
+    home: PathBuf,
+    stack: PathBuf,
+    remote: PathBuf,
+}
+
+impl Workspace {
+    /// Sets up a note stack with one saved note that is synced to an empty bare remote
+    fn new(name: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!("notes-sync-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let home = dir.join("home");
+        let data = home.join(".local/share/notes");
+        let stack = data.join("stacks/main");
+        let remote = dir.join("remote.git");
+
+        std::fs::create_dir_all(&stack).unwrap();
+        std::fs::write(data.join(".notes"), "stack: main").unwrap();
+        std::fs::write(
+            home.join(".gitconfig"),
+            "[user]\n\tname = Notes\n\temail = notes@example.com\n",
+        )
+        .unwrap();
+        Repository::init_bare(&remote).unwrap();
+
+        let workspace = Self {
+            dir,
+            home,
+            stack,
+            remote,
+        };
+        workspace.write("first.md", "First", "first line\n");
+        assert!(workspace.notes(&["save"]).status.success());
+        workspace
+            .repo()
+            .remote("origin", workspace.remote.to_str().unwrap())
+            .unwrap();
+        let output = workspace.notes(&["sync"]);
+        assert!(output.status.success(), "{output:?}");
+        workspace
+    }
+
+    fn notes(&self, args: &[&str]) -> Output {
+        Command::new(env!("CARGO_BIN_EXE_notes"))
+            .args(args)
+            .env("HOME", &self.home)
+            .env_remove("XDG_CONFIG_HOME")
+            .env_remove("NOTES_EDITOR")
+            .output()
+            .unwrap()
+    }
+
+    fn repo(&self) -> Repository {
+        Repository::open(&self.stack).unwrap()
+    }
+
+    fn head(&self) -> Oid {
+        self.repo().head().unwrap().target().unwrap()
+    }
+
+    fn remote_head(&self) -> Oid {
+        let remote = Repository::open_bare(&self.remote).unwrap();
+        remote.head().unwrap().target().unwrap()
+    }
+
+    fn write(&self, path: &str, title: &str, body: &str) {
+        std::fs::write(self.stack.join(path), note(title, body)).unwrap();
+    }
+
+    fn read(&self, path: &str) -> String {
+        std::fs::read_to_string(self.stack.join(path)).unwrap()
+    }
+
+    /// Commits a note from another clone of the remote and pushes it
+    fn push_elsewhere(&self, path: &str, title: &str, body: &str) -> Oid {
+        let other = self.dir.join("other");
+        let _ = std::fs::remove_dir_all(&other);
+        let repo = Repository::clone(self.remote.to_str().unwrap(), &other).unwrap();
+        std::fs::write(other.join(path), note(title, body)).unwrap();
+
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new(path)).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let parent = repo.head().unwrap().peel_to_commit().unwrap();
+        let signature = Signature::now("Other", "other@example.com").unwrap();
+        let oid = repo
+            .commit(
+                Some("HEAD"),
+                &signature,
+                &signature,
+                &format!("update {path}"),
+                &tree,
+                &[&parent],
+            )
+            .unwrap();
+
+        let branch = repo.head().unwrap().name().unwrap().to_owned();
+        repo.find_remote("origin")
+            .unwrap()
+            .push(&[format!("{branch}:{branch}")], None)
+            .unwrap();
+        oid
+    }
+}
+
+impl Drop for Workspace {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+fn note(title: &str, body: &str) -> String {
+    format!(
+        "---\ntitle: {title}\ndate: 2026-01-01T00:00:00+00:00\ntags: null\nnotes_metadata:\n  \
+         category: null\n  subcategories: []\n  hidden: false\n---\n{body}"
+    )
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+#[test]
+fn pushes_and_fast_forwards() {
+    let workspace = Workspace::new("fast-forward");
+    assert_eq!(workspace.head(), workspace.remote_head());
+
+    let oid = workspace.push_elsewhere("second.md", "Second", "from elsewhere\n");
+    let output = workspace.notes(&["sync"]);
+    assert!(output.status.success(), "{output:?}");
+    assert!(stdout(&output).contains("updated from 'origin'"));
+    assert_eq!(workspace.head(), oid);
+    assert!(workspace.read("second.md").contains("from elsewhere"));
+
+    workspace.write("third.md", "Third", "local\n");
+    let output = workspace.notes(&["sync"]);
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(
+        workspace
+            .repo()
+            .head()
+            .unwrap()
+            .peel_to_commit()
+            .unwrap()
+            .parent_id(0)
+            .unwrap(),
+        oid
+    );
+    assert_eq!(workspace.head(), workspace.remote_head());
+}
+
+#[test]
+fn rebases_diverged_commits() {
+    let workspace = Workspace::new("rebase");
+    let oid = workspace.push_elsewhere("second.md", "Second", "from elsewhere\n");
+    workspace.write("third.md", "Third", "local\n");
+
+    let output = workspace.notes(&["sync"]);
+    assert!(output.status.success(), "{output:?}");
+    assert!(stdout(&output).contains("rebased 1 commit(s) onto 'origin'"));
+
+    let repo = workspace.repo();
+    let head = repo.head().unwrap().peel_to_commit().unwrap();
+    assert_eq!(head.parent_count(), 1);
+    assert_eq!(head.parent_id(0).unwrap(), oid);
+    assert_eq!(head.id(), workspace.remote_head());
+    assert!(workspace.read("second.md").contains("from elsewhere"));
+    assert!(workspace.read("third.md").contains("local"));
+}
+
+#[test]
+fn merges_diverged_commits() {
+    let workspace = Workspace::new("merge");
+    let oid = workspace.push_elsewhere("second.md", "Second", "from elsewhere\n");
+    workspace.write("third.md", "Third", "local\n");
+    let saved = {
+        assert!(workspace.notes(&["save"]).status.success());
+        workspace.head()
+    };
+
+    let output = workspace.notes(&["sync", "--merge"]);
+    assert!(output.status.success(), "{output:?}");
+    assert!(stdout(&output).contains("merged notes from 'origin'"));
+
+    let repo = workspace.repo();
+    let head = repo.head().unwrap().peel_to_commit().unwrap();
+    assert_eq!(
+        head.parent_ids().collect::<Vec<_>>(),
+        vec![saved, oid],
+        "a merge commit of the local and remote commits"
+    );
+    assert_eq!(head.id(), workspace.remote_head());
+    assert_eq!(repo.state(), RepositoryState::Clean);
+}
+
+#[test]
+fn lists_conflicts_and_aborts() {
+    let workspace = Workspace::new("conflict");
+    let remote = workspace.push_elsewhere("first.md", "First", "changed elsewhere\n");
+    workspace.write("first.md", "First", "changed locally\n");
+
+    // a conflicting rebase leaves the saved local commit as it was
+    let output = workspace.notes(&["sync"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("conflict: first.md ('First')"));
+    let saved = workspace.head();
+    assert_ne!(saved, remote);
+    assert_eq!(workspace.repo().state(), RepositoryState::Clean);
+    assert!(workspace.read("first.md").contains("changed locally"));
+    assert_eq!(workspace.remote_head(), remote);
+
+    // a conflicting merge is left in progress with conflict markers
+    let output = workspace.notes(&["sync", "--merge"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("conflict: first.md ('First')"));
+    assert_eq!(workspace.repo().state(), RepositoryState::Merge);
+    let contents = workspace.read("first.md");
+    assert!(contents.contains("<<<<<<<"));
+    assert!(contents.contains("changed locally") && contents.contains("changed elsewhere"));
+
+    // concluding the merge is refused while markers remain
+    let output = workspace.notes(&["sync"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("still have conflict markers"));
+
+    let output = workspace.notes(&["sync", "--abort"]);
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(workspace.head(), saved);
+    assert_eq!(workspace.repo().state(), RepositoryState::Clean);
+    assert!(workspace.read("first.md").contains("changed locally"));
+    assert!(!workspace.read("first.md").contains("<<<<<<<"));
+    assert_eq!(workspace.remote_head(), remote);
+}